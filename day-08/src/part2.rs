@@ -1,3 +1,4 @@
+use helpers::cycle::{first_common_step, SignalPeriods};
 use itertools::Itertools;
 use nom::{
     bytes::complete::tag,
@@ -5,7 +6,6 @@ use nom::{
     sequence::{terminated, tuple},
     IResult,
 };
-use num::Integer;
 use std::collections::HashMap;
 
 #[derive(Debug)]
@@ -35,6 +35,9 @@ As a little background for those who may run this themselves:
 - Kept the iteration, but this time just outputted the number, then calculate for the LCM to get the answer
 
 ^^^ This is important as it basically shows that the solution requires a certain kind of input. This is not a general solution.
+
+The period is now measured from the first two end nodes each start node reaches instead of assuming it matches the
+first one, but each start node still has to settle into reaching end nodes at a steady interval.
 */
 
 pub fn process(input: &str) -> String {
@@ -59,30 +62,30 @@ pub fn process(input: &str) -> String {
         .copied()
         .collect_vec();
 
-    node_names
-        .iter()
-        .map(|node_name| {
-            let mut current_node = *node_name;
+    let mut periods = SignalPeriods::new(1);
+    for (ghost, node_name) in node_names.iter().enumerate() {
+        let mut current_node = *node_name;
 
-            instructions
-                .clone()
-                .enumerate()
-                .find_map(|(index, instruction)| {
-                    if current_node.ends_with('Z') {
-                        Some(index)
-                    } else {
-                        current_node = match instruction {
-                            'R' => nodes[current_node].right,
-                            'L' => nodes[current_node].left,
-                            _ => unreachable!(),
-                        };
-                        None
-                    }
-                })
-                .expect("An end node must be found")
-        })
-        .reduce(|acc, steps| acc.lcm(&steps))
-        .expect("The Lowest Common Multiple must be calculated")
+        for (index, instruction) in instructions.clone().enumerate() {
+            if current_node.ends_with('Z') {
+                periods.record(ghost, index);
+                if periods.period(&ghost).is_some() {
+                    break;
+                }
+            }
+            current_node = match instruction {
+                'R' => nodes[current_node].right,
+                'L' => nodes[current_node].left,
+                _ => unreachable!(),
+            };
+        }
+    }
+
+    let periods = periods
+        .periods(&(0..node_names.len()).collect_vec())
+        .expect("An end node must be reached at a steady interval");
+    first_common_step(periods)
+        .expect("Every start node must reach an end node on the same step")
         .to_string()
 }
 
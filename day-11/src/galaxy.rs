@@ -0,0 +1,120 @@
+use itertools::Itertools;
+
+type Point = (usize, usize);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GalaxyMap {
+    galaxies: Vec<Point>,
+    empty_rows_before: Vec<u64>,
+    empty_cols_before: Vec<u64>,
+}
+
+impl GalaxyMap {
+    pub fn galaxies(&self) -> &[Point] {
+        &self.galaxies
+    }
+
+    // Every empty row or column is replaced by `expansion` empty rows or columns,
+    // so a factor of 1 leaves the map untouched.
+    pub fn expanded_position(&self, (row, col): Point, expansion: u64) -> (u64, u64) {
+        let extra = expansion.saturating_sub(1);
+        (
+            row as u64 + self.empty_rows_before[row] * extra,
+            col as u64 + self.empty_cols_before[col] * extra,
+        )
+    }
+
+    pub fn distance(&self, a: usize, b: usize, expansion: u64) -> Option<u64> {
+        let a = self.expanded_position(*self.galaxies.get(a)?, expansion);
+        let b = self.expanded_position(*self.galaxies.get(b)?, expansion);
+
+        Some(a.0.abs_diff(b.0) + a.1.abs_diff(b.1))
+    }
+
+    pub fn pairwise_distance_sum(&self, expansion: u64) -> u64 {
+        let (rows, cols): (Vec<u64>, Vec<u64>) = self
+            .galaxies
+            .iter()
+            .map(|&galaxy| self.expanded_position(galaxy, expansion))
+            .unzip();
+
+        // Galaxies are collected row by row, so only the columns need sorting.
+        sorted_pairwise_sum(&rows) + sorted_pairwise_sum(&cols.into_iter().sorted().collect_vec())
+    }
+}
+
+// For sorted values, the i-th value is larger than all i values before it, so its
+// contribution to the pairwise differences is `i * value - (sum of previous values)`.
+fn sorted_pairwise_sum(values: &[u64]) -> u64 {
+    values
+        .iter()
+        .enumerate()
+        .fold((0, 0), |(total, prefix), (index, &value)| {
+            (total + index as u64 * value - prefix, prefix + value)
+        })
+        .0
+}
+
+fn empty_before(occupied: &[bool]) -> Vec<u64> {
+    occupied
+        .iter()
+        .scan(0, |empty, &occupied| {
+            let before = *empty;
+            *empty += u64::from(!occupied);
+            Some(before)
+        })
+        .collect()
+}
+
+impl From<&str> for GalaxyMap {
+    fn from(input: &str) -> Self {
+        let rows = input.lines().count();
+        let cols = input.lines().next().map_or(0, |line| line.len());
+
+        let galaxies = input
+            .lines()
+            .enumerate()
+            .flat_map(|(row, line)| {
+                line.chars()
+                    .enumerate()
+                    .filter_map(move |(col, pixel)| pixel.eq(&'#').then_some((row, col)))
+            })
+            .collect_vec();
+
+        let mut occupied_rows = vec![false; rows];
+        let mut occupied_cols = vec![false; cols];
+        for &(row, col) in &galaxies {
+            occupied_rows[row] = true;
+            occupied_cols[col] = true;
+        }
+
+        Self {
+            empty_rows_before: empty_before(&occupied_rows),
+            empty_cols_before: empty_before(&occupied_cols),
+            galaxies,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pairwise_distance_sum() {
+        let map = GalaxyMap::from(include_str!("../data/example.txt"));
+        assert_eq!(map.pairwise_distance_sum(2), 374);
+        assert_eq!(map.pairwise_distance_sum(10), 1030);
+        assert_eq!(map.pairwise_distance_sum(100), 8410);
+    }
+
+    #[test]
+    fn test_distance() {
+        let map = GalaxyMap::from(include_str!("../data/example.txt"));
+        assert_eq!(map.distance(4, 8, 2), Some(9));
+        assert_eq!(map.distance(0, 6, 2), Some(15));
+        assert_eq!(map.distance(2, 5, 2), Some(17));
+        assert_eq!(map.distance(7, 8, 2), Some(5));
+        assert_eq!(map.distance(0, 9, 2), None);
+    }
+}
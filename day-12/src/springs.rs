@@ -0,0 +1,327 @@
+use itertools::Itertools;
+
+// (completed groups, length of the damaged run currently being built)
+type State = (usize, usize);
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum Spring {
+    Operational,
+    Damaged,
+    Unknown,
+}
+
+impl Spring {
+    // The concrete springs this one could be, in lexicographic order.
+    fn choices(&self) -> &'static [Spring] {
+        match self {
+            Self::Operational => &[Self::Operational],
+            Self::Damaged => &[Self::Damaged],
+            Self::Unknown => &[Self::Operational, Self::Damaged],
+        }
+    }
+}
+
+impl From<char> for Spring {
+    fn from(value: char) -> Self {
+        match value {
+            '.' => Self::Operational,
+            '#' => Self::Damaged,
+            '?' => Self::Unknown,
+            _ => unreachable!("Must match one of these characters"),
+        }
+    }
+}
+
+impl From<Spring> for char {
+    fn from(value: Spring) -> Self {
+        match value {
+            Spring::Operational => '.',
+            Spring::Damaged => '#',
+            Spring::Unknown => '?',
+        }
+    }
+}
+
+pub fn render(springs: &[Spring]) -> String {
+    springs.iter().map(|&spring| char::from(spring)).collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Record {
+    springs: Vec<Spring>,
+    groups: Vec<usize>,
+}
+
+impl Record {
+    pub fn springs(&self) -> &[Spring] {
+        &self.springs
+    }
+
+    pub fn groups(&self) -> &[usize] {
+        &self.groups
+    }
+
+    pub fn unfold(&self, factor: usize) -> Self {
+        Self {
+            springs: Itertools::intersperse(
+                (0..factor).map(|_| self.springs.as_slice()),
+                &[Spring::Unknown],
+            )
+            .flatten()
+            .copied()
+            .collect(),
+            groups: self.groups.repeat(factor),
+        }
+    }
+
+    fn widths(&self) -> usize {
+        self.groups.len() + 1
+    }
+
+    fn runs(&self) -> usize {
+        self.groups.iter().max().map_or(1, |max| max + 1)
+    }
+
+    fn index(&self, (group, run): State) -> usize {
+        group * self.runs() + run
+    }
+
+    // Moves the state along by one concrete spring, if that spring is allowed here.
+    fn advance(&self, (group, run): State, spring: Spring) -> Option<State> {
+        match spring {
+            Spring::Operational if run == 0 => Some((group, 0)),
+            Spring::Operational => (self.groups.get(group) == Some(&run)).then_some((group + 1, 0)),
+            Spring::Damaged => self
+                .groups
+                .get(group)
+                .is_some_and(|&size| run < size)
+                .then_some((group, run + 1)),
+            Spring::Unknown => unreachable!("Only concrete springs can be advanced"),
+        }
+    }
+
+    fn is_complete(&self, state: State) -> bool {
+        let groups = self.groups.len();
+        state == (groups, 0)
+            || groups
+                .checked_sub(1)
+                .is_some_and(|last| state == (last, self.groups[last]))
+    }
+
+    fn states(&self) -> impl Iterator<Item = State> {
+        (0..self.widths()).cartesian_product(0..self.runs())
+    }
+
+    pub fn arrangements(&self) -> usize {
+        // Only two rows of positions are ever needed when counting forwards.
+        let mut current = vec![0_usize; self.widths() * self.runs()];
+        let mut next = current.clone();
+        current[self.index((0, 0))] = 1;
+
+        for spring in &self.springs {
+            next.fill(0);
+
+            for state in self.states() {
+                let ways = current[self.index(state)];
+                if ways == 0 {
+                    continue;
+                }
+
+                for &choice in spring.choices() {
+                    if let Some(next_state) = self.advance(state, choice) {
+                        next[self.index(next_state)] += ways;
+                    }
+                }
+            }
+
+            std::mem::swap(&mut current, &mut next);
+        }
+
+        self.states()
+            .filter(|&state| self.is_complete(state))
+            .map(|state| current[self.index(state)])
+            .sum()
+    }
+
+    // The number of ways to finish the record from each state at each position,
+    // which is what lets arrangements be ranked without enumerating them.
+    fn completions(&self) -> Vec<Vec<usize>> {
+        let mut table = vec![vec![0_usize; self.widths() * self.runs()]; self.springs.len() + 1];
+
+        for state in self.states() {
+            table[self.springs.len()][self.index(state)] = usize::from(self.is_complete(state));
+        }
+
+        for position in (0..self.springs.len()).rev() {
+            for state in self.states() {
+                table[position][self.index(state)] = self.springs[position]
+                    .choices()
+                    .iter()
+                    .filter_map(|&choice| self.advance(state, choice))
+                    .map(|next_state| table[position + 1][self.index(next_state)])
+                    .sum();
+            }
+        }
+
+        table
+    }
+
+    fn unrank(&self, table: &[Vec<usize>], mut rank: usize) -> Option<Vec<Spring>> {
+        if rank >= table[0][self.index((0, 0))] {
+            return None;
+        }
+
+        let mut state = (0, 0);
+        let mut arrangement = Vec::with_capacity(self.springs.len());
+
+        for (position, spring) in self.springs.iter().enumerate() {
+            for &choice in spring.choices() {
+                let Some(next_state) = self.advance(state, choice) else {
+                    continue;
+                };
+
+                let ways = table[position + 1][self.index(next_state)];
+                if rank < ways {
+                    arrangement.push(choice);
+                    state = next_state;
+                    break;
+                }
+
+                rank -= ways;
+            }
+        }
+
+        Some(arrangement)
+    }
+
+    // Arrangements are ordered lexicographically with operational before damaged.
+    pub fn nth_arrangement(&self, rank: usize) -> Option<Vec<Spring>> {
+        self.unrank(&self.completions(), rank)
+    }
+
+    pub fn iter_arrangements(&self) -> Arrangements<'_> {
+        let table = self.completions();
+
+        Arrangements {
+            total: table[0][self.index((0, 0))],
+            record: self,
+            table,
+            rank: 0,
+        }
+    }
+
+    pub fn is_satisfied_by(&self, filled: &[Spring]) -> bool {
+        filled.len() == self.springs.len()
+            && filled.iter().zip(&self.springs).all(|(spring, known)| {
+                spring != &Spring::Unknown && known.choices().contains(spring)
+            })
+            && filled
+                .iter()
+                .group_by(|&&spring| spring)
+                .into_iter()
+                .filter(|(spring, _)| spring == &Spring::Damaged)
+                .map(|(_, group)| group.count())
+                .eq(self.groups.iter().copied())
+    }
+}
+
+impl From<&str> for Record {
+    fn from(line: &str) -> Self {
+        let (springs, groups) = line.split_once(' ').unwrap_or((line, ""));
+
+        Self {
+            springs: springs.chars().map(Spring::from).collect_vec(),
+            groups: groups
+                .split(',')
+                .filter_map(|n| n.parse::<usize>().ok())
+                .collect_vec(),
+        }
+    }
+}
+
+pub struct Arrangements<'a> {
+    record: &'a Record,
+    table: Vec<Vec<usize>>,
+    rank: usize,
+    total: usize,
+}
+
+impl Iterator for Arrangements<'_> {
+    type Item = Vec<Spring>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let arrangement = self.record.unrank(&self.table, self.rank)?;
+        self.rank += 1;
+        Some(arrangement)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.total - self.rank;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Arrangements<'_> {}
+
+impl<'a> Arrangements<'a> {
+    pub fn rendered(self) -> impl Iterator<Item = String> + 'a {
+        self.map(|arrangement| render(&arrangement))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arrangements() {
+        let counts = include_str!("../data/example.txt")
+            .lines()
+            .map(|line| Record::from(line).arrangements())
+            .collect_vec();
+
+        assert_eq!(counts, vec![1, 4, 1, 1, 4, 10]);
+    }
+
+    #[test]
+    fn test_unfolded_arrangements() {
+        let counts = include_str!("../data/example.txt")
+            .lines()
+            .map(|line| Record::from(line).unfold(5).arrangements())
+            .collect_vec();
+
+        assert_eq!(counts, vec![1, 16384, 1, 16, 2500, 506250]);
+    }
+
+    #[test]
+    fn test_iter_arrangements() {
+        let record = Record::from(".??..??...?##. 1,1,3");
+        let arrangements = record.iter_arrangements().rendered().collect_vec();
+
+        assert_eq!(
+            arrangements,
+            vec![
+                "..#...#...###.",
+                "..#..#....###.",
+                ".#....#...###.",
+                ".#...#....###.",
+            ]
+        );
+        assert_eq!(record.nth_arrangement(2), record.iter_arrangements().nth(2));
+        assert_eq!(record.nth_arrangement(4), None);
+    }
+
+    #[test]
+    fn test_is_satisfied_by() {
+        let record = Record::from("?###???????? 3,2,1");
+        let filled = |row: &str| row.chars().map(Spring::from).collect_vec();
+
+        assert!(record.is_satisfied_by(&filled(".###.##.#...")));
+        assert!(!record.is_satisfied_by(&filled(".###.##.##..")));
+        assert!(!record.is_satisfied_by(&filled("####.##.#...")));
+        assert!(!record.is_satisfied_by(&filled(".###.##.#..?")));
+        assert!(record
+            .iter_arrangements()
+            .all(|arrangement| record.is_satisfied_by(&arrangement)));
+    }
+}
@@ -0,0 +1,181 @@
+use itertools::Itertools;
+
+type Point = (usize, usize);
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Orientation {
+    // A mirror between two rows
+    Horizontal,
+    // A mirror between two columns
+    Vertical,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Reflection {
+    pub orientation: Orientation,
+    // The number of rows above or columns left of the mirror
+    pub offset: usize,
+}
+
+impl Reflection {
+    pub fn summary(&self) -> usize {
+        match self.orientation {
+            Orientation::Horizontal => self.offset * 100,
+            Orientation::Vertical => self.offset,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Pattern {
+    // Bit `col` of each row (and bit `row` of each column) is set for every rock.
+    rows: Vec<u64>,
+    cols: Vec<u64>,
+}
+
+impl Pattern {
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn width(&self) -> usize {
+        self.cols.len()
+    }
+
+    fn lines(&self, orientation: Orientation) -> &[u64] {
+        match orientation {
+            Orientation::Horizontal => &self.rows,
+            Orientation::Vertical => &self.cols,
+        }
+    }
+
+    // Each pair of lines mirrored across the reflection, nearest to the mirror first.
+    fn mirrored_pairs(reflection: &Reflection, len: usize) -> impl Iterator<Item = (usize, usize)> {
+        (0..reflection.offset).rev().zip(reflection.offset..len)
+    }
+
+    fn differences(&self, reflection: &Reflection) -> usize {
+        let lines = self.lines(reflection.orientation);
+
+        Self::mirrored_pairs(reflection, lines.len())
+            .map(|(a, b)| (lines[a] ^ lines[b]).count_ones() as usize)
+            .sum()
+    }
+
+    pub fn find_reflections(&self, smudges: usize) -> Vec<Reflection> {
+        [Orientation::Horizontal, Orientation::Vertical]
+            .into_iter()
+            .flat_map(|orientation| {
+                (1..self.lines(orientation).len()).map(move |offset| Reflection {
+                    orientation,
+                    offset,
+                })
+            })
+            .filter(|reflection| self.differences(reflection) == smudges)
+            .collect_vec()
+    }
+
+    // Fixing either cell of a pair removes that smudge.
+    pub fn smudges(&self, reflection: &Reflection) -> Vec<(Point, Point)> {
+        let lines = self.lines(reflection.orientation);
+        let cells = match reflection.orientation {
+            Orientation::Horizontal => self.width(),
+            Orientation::Vertical => self.height(),
+        };
+
+        Self::mirrored_pairs(reflection, lines.len())
+            .flat_map(|(a, b)| {
+                (0..cells)
+                    .filter(move |cell| (lines[a] ^ lines[b]) & (1 << cell) != 0)
+                    .map(move |cell| match reflection.orientation {
+                        Orientation::Horizontal => ((a, cell), (b, cell)),
+                        Orientation::Vertical => ((cell, a), (cell, b)),
+                    })
+            })
+            .sorted()
+            .collect_vec()
+    }
+}
+
+impl From<&str> for Pattern {
+    fn from(input: &str) -> Self {
+        let height = input.lines().count();
+        let width = input.lines().next().map_or(0, |line| line.len());
+        assert!(
+            height <= 64 && width <= 64,
+            "A pattern must fit in 64 rows and columns"
+        );
+
+        let mut rows = vec![0; height];
+        let mut cols = vec![0; width];
+        for (row, line) in input.lines().enumerate() {
+            for (col, _) in line.chars().enumerate().filter(|(_, c)| c == &'#') {
+                rows[row] |= 1 << col;
+                cols[col] |= 1 << row;
+            }
+        }
+
+        Self { rows, cols }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns() -> Vec<Pattern> {
+        include_str!("../data/example.txt")
+            .split("\n\n")
+            .map(Pattern::from)
+            .collect_vec()
+    }
+
+    #[test]
+    fn test_find_reflections() {
+        use Orientation::*;
+
+        let patterns = patterns();
+        assert_eq!(
+            patterns[0].find_reflections(0),
+            vec![Reflection {
+                orientation: Vertical,
+                offset: 5
+            }]
+        );
+        assert_eq!(
+            patterns[1].find_reflections(0),
+            vec![Reflection {
+                orientation: Horizontal,
+                offset: 4
+            }]
+        );
+        assert_eq!(
+            patterns[0].find_reflections(1),
+            vec![Reflection {
+                orientation: Horizontal,
+                offset: 3
+            }]
+        );
+        assert_eq!(
+            patterns[1].find_reflections(1),
+            vec![Reflection {
+                orientation: Horizontal,
+                offset: 1
+            }]
+        );
+    }
+
+    #[test]
+    fn test_smudges() {
+        let patterns = patterns();
+        let smudges = patterns
+            .iter()
+            .map(|pattern| pattern.smudges(&pattern.find_reflections(1)[0]))
+            .collect_vec();
+
+        assert_eq!(
+            smudges,
+            vec![vec![((0, 0), (5, 0))], vec![((0, 4), (1, 4))]]
+        );
+    }
+}
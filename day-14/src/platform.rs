@@ -0,0 +1,203 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+use grid::Grid;
+use helpers::cycle::{brent_by_key, Cycle};
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Tile {
+    Empty,
+    Round,
+    Cube,
+}
+
+impl From<char> for Tile {
+    fn from(value: char) -> Self {
+        match value {
+            '.' => Self::Empty,
+            'O' => Self::Round,
+            '#' => Self::Cube,
+            _ => unreachable!("Must match one of these characters"),
+        }
+    }
+}
+
+impl From<Tile> for char {
+    fn from(value: Tile) -> Self {
+        match value {
+            Tile::Empty => '.',
+            Tile::Round => 'O',
+            Tile::Cube => '#',
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Direction {
+    North,
+    South,
+    East,
+    West,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Platform {
+    tiles: Grid<Tile>,
+}
+
+impl Platform {
+    pub fn tilt(&mut self, direction: Direction) {
+        use Direction::*;
+
+        let (rows, cols) = self.tiles.size();
+        let (lines, length) = match direction {
+            North | South => (cols, rows),
+            East | West => (rows, cols),
+        };
+
+        // Step 0 of every line is the edge the rocks roll towards.
+        let position = |line: usize, step: usize| match direction {
+            North => (step, line),
+            South => (rows - 1 - step, line),
+            West => (line, step),
+            East => (line, cols - 1 - step),
+        };
+
+        for line in 0..lines {
+            let mut free = 0;
+
+            for step in 0..length {
+                match self.tiles[position(line, step)] {
+                    Tile::Cube => free = step + 1,
+                    Tile::Round => {
+                        self.tiles[position(line, step)] = Tile::Empty;
+                        self.tiles[position(line, free)] = Tile::Round;
+                        free += 1;
+                    }
+                    Tile::Empty => {}
+                }
+            }
+        }
+    }
+
+    pub fn spin_cycle(&mut self) {
+        for direction in [
+            Direction::North,
+            Direction::West,
+            Direction::South,
+            Direction::East,
+        ] {
+            self.tilt(direction);
+        }
+    }
+
+    pub fn state_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.tiles.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    pub fn find_spin_cycle(&self) -> Cycle {
+        brent_by_key(
+            self,
+            |platform| {
+                let mut platform = platform.clone();
+                platform.spin_cycle();
+                platform
+            },
+            Platform::state_hash,
+        )
+    }
+
+    pub fn spin_cycles(&mut self, cycles: usize) {
+        for _ in 0..self.find_spin_cycle().reduce(cycles) {
+            self.spin_cycle();
+        }
+    }
+
+    pub fn load(&self) -> usize {
+        self.tiles
+            .indexed_iter()
+            .filter(|(_, tile)| tile == &&Tile::Round)
+            .map(|((row, _), _)| self.tiles.rows() - row)
+            .sum()
+    }
+
+    pub fn load_after(&self, cycles: usize) -> usize {
+        let mut platform = self.clone();
+        platform.spin_cycles(cycles);
+        platform.load()
+    }
+}
+
+impl From<&str> for Platform {
+    fn from(input: &str) -> Self {
+        Self {
+            tiles: Grid::from_vec(
+                input
+                    .lines()
+                    .flat_map(|line| line.chars().map(Tile::from))
+                    .collect(),
+                input.lines().next().map_or(0, |row| row.len()),
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.tiles.iter_rows() {
+            writeln!(
+                f,
+                "{}",
+                row.map(|&tile| char::from(tile)).collect::<String>()
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spin_cycle() {
+        let mut platform = Platform::from(include_str!("../data/example.txt"));
+        platform.spin_cycle();
+        platform.spin_cycle();
+        platform.spin_cycle();
+
+        let expected = [
+            ".....#....",
+            "....#...O#",
+            ".....##...",
+            "..O#......",
+            ".....OOO#.",
+            ".O#...O#.#",
+            "....O#...O",
+            ".......OOO",
+            "#...O###.O",
+            "#.OOO#...O",
+        ];
+        assert_eq!(platform.to_string(), expected.join("\n") + "\n");
+    }
+
+    #[test]
+    fn test_load_after() {
+        let platform = Platform::from(include_str!("../data/example.txt"));
+        let loads = (0..20)
+            .map(|cycles| platform.load_after(cycles))
+            .collect::<Vec<_>>();
+
+        let mut expected = platform.clone();
+        for (cycles, load) in loads.into_iter().enumerate() {
+            assert_eq!(load, expected.load(), "after {cycles} cycles");
+            expected.spin_cycle();
+        }
+    }
+}
@@ -0,0 +1,241 @@
+use std::fmt;
+
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take_till1},
+    character::complete,
+    combinator::{all_consuming, map, value},
+    sequence::{pair, preceded},
+    IResult,
+};
+
+pub fn holiday_hash(bytes: &[u8]) -> u8 {
+    bytes
+        .iter()
+        .fold(0_u8, |acc, &byte| acc.wrapping_add(byte).wrapping_mul(17))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operation {
+    Insert(u8),
+    Remove,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Step<'a> {
+    pub label: &'a str,
+    pub operation: Operation,
+}
+
+impl<'a> Step<'a> {
+    fn parse(input: &'a str) -> IResult<&'a str, Self> {
+        map(
+            pair(
+                take_till1(|c| c == '=' || c == '-'),
+                alt((
+                    map(preceded(tag("="), complete::u8), Operation::Insert),
+                    value(Operation::Remove, tag("-")),
+                )),
+            ),
+            |(label, operation)| Self { label, operation },
+        )(input)
+    }
+}
+
+impl<'a> From<&'a str> for Step<'a> {
+    fn from(input: &'a str) -> Self {
+        let (_, step) = all_consuming(Self::parse)(input).expect("Must match the parser");
+        step
+    }
+}
+
+impl fmt::Display for Step<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.operation {
+            Operation::Insert(focal_length) => write!(f, "{}={}", self.label, focal_length),
+            Operation::Remove => write!(f, "{}-", self.label),
+        }
+    }
+}
+
+pub fn steps(input: &str) -> impl Iterator<Item = Step<'_>> {
+    input.trim_end().split(',').map(Step::from)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Lens<'a> {
+    pub label: &'a str,
+    pub focal_length: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LensBoxes<'a> {
+    boxes: [Vec<Lens<'a>>; 256],
+}
+
+impl Default for LensBoxes<'_> {
+    fn default() -> Self {
+        Self {
+            boxes: std::array::from_fn(|_| Vec::new()),
+        }
+    }
+}
+
+impl<'a> LensBoxes<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lenses(&mut self, label: &str) -> &mut Vec<Lens<'a>> {
+        &mut self.boxes[holiday_hash(label.as_bytes()) as usize]
+    }
+
+    // Replaces a lens with the same label in place, otherwise adds it to the back.
+    pub fn insert(&mut self, label: &'a str, focal_length: u8) {
+        let lenses = self.lenses(label);
+
+        match lenses.iter_mut().find(|lens| lens.label == label) {
+            Some(lens) => lens.focal_length = focal_length,
+            None => lenses.push(Lens {
+                label,
+                focal_length,
+            }),
+        }
+    }
+
+    pub fn remove(&mut self, label: &str) -> Option<Lens<'a>> {
+        let lenses = self.lenses(label);
+        let position = lenses.iter().position(|lens| lens.label == label)?;
+        Some(lenses.remove(position))
+    }
+
+    pub fn apply(&mut self, step: Step<'a>) {
+        match step.operation {
+            Operation::Insert(focal_length) => self.insert(step.label, focal_length),
+            Operation::Remove => {
+                self.remove(step.label);
+            }
+        }
+    }
+
+    pub fn get(&self, index: u8) -> &[Lens<'a>] {
+        &self.boxes[index as usize]
+    }
+
+    // Every box holding at least one lens, with its box number.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &[Lens<'a>])> {
+        self.boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, lenses)| !lenses.is_empty())
+            .map(|(index, lenses)| (index, lenses.as_slice()))
+    }
+
+    pub fn lens_focusing_power(&self, label: &str) -> Option<usize> {
+        let index = holiday_hash(label.as_bytes()) as usize;
+
+        self.boxes[index]
+            .iter()
+            .position(|lens| lens.label == label)
+            .map(|slot| (index + 1) * (slot + 1) * self.boxes[index][slot].focal_length as usize)
+    }
+
+    pub fn box_focusing_power(&self, index: u8) -> usize {
+        self.get(index)
+            .iter()
+            .enumerate()
+            .map(|(slot, lens)| (index as usize + 1) * (slot + 1) * lens.focal_length as usize)
+            .sum()
+    }
+
+    pub fn focusing_power(&self) -> usize {
+        (0..=u8::MAX)
+            .map(|index| self.box_focusing_power(index))
+            .sum()
+    }
+}
+
+impl<'a> Extend<Step<'a>> for LensBoxes<'a> {
+    fn extend<T: IntoIterator<Item = Step<'a>>>(&mut self, steps: T) {
+        steps.into_iter().for_each(|step| self.apply(step));
+    }
+}
+
+impl<'a> FromIterator<Step<'a>> for LensBoxes<'a> {
+    fn from_iter<T: IntoIterator<Item = Step<'a>>>(steps: T) -> Self {
+        let mut boxes = Self::new();
+        boxes.extend(steps);
+        boxes
+    }
+}
+
+impl fmt::Display for LensBoxes<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, lenses) in self.iter() {
+            write!(f, "Box {}:", index)?;
+            for lens in lenses {
+                write!(f, " [{} {}]", lens.label, lens.focal_length)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+// The box states after each step, in the same format as the puzzle text.
+pub fn trace(input: &str) -> String {
+    let mut boxes = LensBoxes::new();
+
+    steps(input)
+        .map(|step| {
+            boxes.apply(step);
+            format!("After \"{}\":\n{}", step, boxes)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_holiday_hash() {
+        assert_eq!(holiday_hash(b"HASH"), 52);
+        assert_eq!(holiday_hash(b"rn"), 0);
+        assert_eq!(holiday_hash(b"qp"), 1);
+        assert_eq!(holiday_hash(b"pc"), 3);
+    }
+
+    #[test]
+    fn test_lens_boxes() {
+        let boxes = steps(include_str!("../data/example.txt")).collect::<LensBoxes>();
+
+        assert_eq!(
+            boxes.to_string(),
+            "Box 0: [rn 1] [cm 2]\nBox 3: [ot 7] [ab 5] [pc 6]\n"
+        );
+        assert_eq!(boxes.lens_focusing_power("ab"), Some(40));
+        assert_eq!(boxes.lens_focusing_power("qp"), None);
+        assert_eq!(boxes.box_focusing_power(3), 28 + 40 + 72);
+        assert_eq!(boxes.focusing_power(), 145);
+    }
+
+    #[test]
+    fn test_trace() {
+        let trace = trace("rn=1,cm-,qp=3,cm=2,qp-");
+
+        assert_eq!(
+            trace,
+            [
+                "After \"rn=1\":\nBox 0: [rn 1]\n",
+                "After \"cm-\":\nBox 0: [rn 1]\n",
+                "After \"qp=3\":\nBox 0: [rn 1]\nBox 1: [qp 3]\n",
+                "After \"cm=2\":\nBox 0: [rn 1] [cm 2]\nBox 1: [qp 3]\n",
+                "After \"qp-\":\nBox 0: [rn 1] [cm 2]\n",
+            ]
+            .join("\n")
+        );
+    }
+}
@@ -1,10 +1,10 @@
+use crate::lens::holiday_hash;
+
 pub fn process(input: &str) -> String {
     input
+        .trim_end()
         .split(',')
-        .map(|item| {
-            item.chars()
-                .fold(0, |acc, c| ((acc + c as usize) * 17) % 256)
-        })
+        .map(|item| holiday_hash(item.as_bytes()) as usize)
         .sum::<usize>()
         .to_string()
 }
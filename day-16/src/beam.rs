@@ -0,0 +1,278 @@
+use std::fmt;
+
+use grid::Grid;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::optic::{Direction, Directions, Optics};
+
+type Point = (usize, usize);
+
+fn advance(direction: Direction, (row, col): Point, (rows, cols): (usize, usize)) -> Option<Point> {
+    match direction {
+        Direction::North => row.checked_sub(1).map(|row| (row, col)),
+        Direction::South => (row + 1 < rows).then_some((row + 1, col)),
+        Direction::East => (col + 1 < cols).then_some((row, col + 1)),
+        Direction::West => col.checked_sub(1).map(|col| (row, col)),
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Beam {
+    pub point: Point,
+    pub direction: Direction,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Energized {
+    // The directions beams have entered each tile in
+    directions: Grid<Directions>,
+}
+
+impl Energized {
+    pub fn count(&self) -> usize {
+        self.directions
+            .iter()
+            .filter(|directions| !directions.is_empty())
+            .count()
+    }
+
+    pub fn contains(&self, (row, col): Point) -> bool {
+        self.directions
+            .get(row, col)
+            .is_some_and(|directions| !directions.is_empty())
+    }
+
+    pub fn directions(&self, (row, col): Point) -> Directions {
+        self.directions.get(row, col).copied().unwrap_or_default()
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        self.directions
+            .indexed_iter()
+            .filter(|(_, directions)| !directions.is_empty())
+            .map(|(point, _)| point)
+    }
+}
+
+impl fmt::Display for Energized {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.directions.iter_rows() {
+            let line = row
+                .map(|directions| if directions.is_empty() { '.' } else { '#' })
+                .collect::<String>();
+            writeln!(f, "{}", line)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contraption {
+    // Each tile is an index into the symbols and deflections below
+    tiles: Grid<usize>,
+    symbols: Vec<char>,
+    // The outgoing directions for each incoming direction, in `Direction::ALL` order
+    deflections: Vec<[Directions; 4]>,
+}
+
+impl Contraption {
+    pub fn new(input: &str, optics: &Optics) -> Self {
+        let mut symbols: Vec<char> = Vec::new();
+        let mut deflections = Vec::new();
+
+        let tiles = input
+            .lines()
+            .flat_map(|line| line.chars())
+            .map(|symbol| {
+                if let Some(index) = symbols.iter().position(|&known| known == symbol) {
+                    return index;
+                }
+
+                let optic = optics
+                    .get(symbol)
+                    .unwrap_or_else(|| panic!("Must have an optic registered for {symbol:?}"));
+                symbols.push(symbol);
+                deflections.push(Direction::ALL.map(|direction| optic.deflect(direction)));
+                symbols.len() - 1
+            })
+            .collect();
+
+        Self {
+            tiles: Grid::from_vec(tiles, input.lines().next().map_or(0, |row| row.len())),
+            symbols,
+            deflections,
+        }
+    }
+
+    pub fn energize(&self, start: Beam) -> Energized {
+        let size = self.tiles.size();
+        let mut directions: Grid<Directions> = Grid::new(size.0, size.1);
+        let mut beams = vec![start];
+
+        while let Some(Beam { point, direction }) = beams.pop() {
+            if !directions[point].insert(direction) {
+                continue;
+            }
+
+            beams.extend(
+                self.deflections[self.tiles[point]][direction as usize]
+                    .iter()
+                    .filter_map(|direction| {
+                        advance(direction, point, size).map(|point| Beam { point, direction })
+                    }),
+            );
+        }
+
+        Energized { directions }
+    }
+
+    pub fn entrances(&self) -> Vec<Beam> {
+        let (rows, cols) = self.tiles.size();
+
+        (0..cols)
+            .flat_map(|col| {
+                [
+                    Beam {
+                        point: (0, col),
+                        direction: Direction::South,
+                    },
+                    Beam {
+                        point: (rows - 1, col),
+                        direction: Direction::North,
+                    },
+                ]
+            })
+            .chain((0..rows).flat_map(|row| {
+                [
+                    Beam {
+                        point: (row, 0),
+                        direction: Direction::East,
+                    },
+                    Beam {
+                        point: (row, cols - 1),
+                        direction: Direction::West,
+                    },
+                ]
+            }))
+            .collect()
+    }
+
+    pub fn most_energized(&self) -> Option<(Beam, Energized)> {
+        self.entrances()
+            .into_par_iter()
+            .map(|beam| (beam, self.energize(beam)))
+            .max_by_key(|(_, energized)| energized.count())
+    }
+
+    // Empty tiles show the beams crossing them like the puzzle text: an arrow for a
+    // single beam, otherwise the number of beams.
+    pub fn render(&self, energized: &Energized) -> String {
+        let mut output = String::new();
+
+        for (row, tiles) in self.tiles.iter_rows().enumerate() {
+            for (col, &tile) in tiles.enumerate() {
+                let directions = energized.directions((row, col));
+                output.push(match (self.symbols[tile], directions.len()) {
+                    ('.', 1) => directions.iter().next().map_or('.', |d| d.arrow()),
+                    ('.', count @ 2..) => char::from_digit(count as u32, 10).unwrap_or('*'),
+                    (symbol, _) => symbol,
+                });
+            }
+            output.push('\n');
+        }
+
+        output + &format!("{} tiles energized\n", energized.count())
+    }
+}
+
+impl From<&str> for Contraption {
+    fn from(input: &str) -> Self {
+        Self::new(input, &Optics::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optic::{Element, OneWayMirror};
+
+    const START: Beam = Beam {
+        point: (0, 0),
+        direction: Direction::East,
+    };
+
+    #[test]
+    fn test_energize() {
+        let contraption = Contraption::from(include_str!("../data/example.txt"));
+        let energized = contraption.energize(START);
+
+        assert_eq!(energized.count(), 46);
+        assert_eq!(energized.points().count(), 46);
+        assert!(energized.contains((7, 1)));
+        assert!(!energized.contains((9, 0)));
+        assert_eq!(
+            energized.directions((0, 1)).iter().collect::<Vec<_>>(),
+            vec![Direction::East, Direction::West]
+        );
+    }
+
+    #[test]
+    fn test_most_energized() {
+        let contraption = Contraption::from(include_str!("../data/example.txt"));
+        let (beam, energized) = contraption.most_energized().unwrap();
+
+        assert_eq!(
+            beam,
+            Beam {
+                point: (0, 3),
+                direction: Direction::South
+            }
+        );
+        assert_eq!(energized.count(), 51);
+    }
+
+    #[test]
+    fn test_render() {
+        let contraption = Contraption::from(include_str!("../data/example.txt"));
+        let expected = [
+            ">|<<<\\....",
+            "|v-.\\^....",
+            ".v...|->>>",
+            ".v...v^.|.",
+            ".v...v^...",
+            ".v...v^..\\",
+            ".v../2\\\\..",
+            "<->-/vv|..",
+            ".|<<<2-|.\\",
+            ".v//.|.v..",
+            "46 tiles energized",
+        ];
+
+        assert_eq!(
+            contraption.render(&contraption.energize(START)),
+            expected.join("\n") + "\n"
+        );
+    }
+
+    #[test]
+    fn test_custom_optics() {
+        let optics = Optics::default().with('#', Element::Absorber).with(
+            '>',
+            OneWayMirror {
+                mirror: Element::BackwardMirror,
+                open: Direction::East,
+            },
+        );
+        let contraption = Contraption::new("..#\n.>.\n...", &optics);
+
+        let absorbed = contraption.energize(START);
+        assert_eq!(absorbed.to_string(), "###\n...\n...\n");
+
+        let reflected = contraption.energize(Beam {
+            point: (1, 2),
+            direction: Direction::West,
+        });
+        assert_eq!(reflected.to_string(), ".#.\n.##\n...\n");
+    }
+}
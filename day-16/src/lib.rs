@@ -1,2 +1,4 @@
+pub mod beam;
+pub mod optic;
 pub mod part1;
 pub mod part2;
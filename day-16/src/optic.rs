@@ -0,0 +1,204 @@
+use std::{collections::HashMap, fmt, sync::Arc};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Direction {
+    North,
+    South,
+    East,
+    West,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::South,
+        Direction::East,
+        Direction::West,
+    ];
+
+    fn bit(&self) -> u8 {
+        match self {
+            Self::North => 1,
+            Self::South => 2,
+            Self::East => 4,
+            Self::West => 8,
+        }
+    }
+
+    pub fn arrow(&self) -> char {
+        match self {
+            Self::North => '^',
+            Self::South => 'v',
+            Self::East => '>',
+            Self::West => '<',
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Directions(u8);
+
+impl Directions {
+    pub fn contains(&self, direction: Direction) -> bool {
+        self.0 & direction.bit() != 0
+    }
+
+    pub fn insert(&mut self, direction: Direction) -> bool {
+        let inserted = !self.contains(direction);
+        self.0 |= direction.bit();
+        inserted
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Direction> {
+        let directions = *self;
+        Direction::ALL
+            .into_iter()
+            .filter(move |&direction| directions.contains(direction))
+    }
+}
+
+impl From<Direction> for Directions {
+    fn from(direction: Direction) -> Self {
+        Self(direction.bit())
+    }
+}
+
+impl FromIterator<Direction> for Directions {
+    fn from_iter<T: IntoIterator<Item = Direction>>(iter: T) -> Self {
+        let mut directions = Self::default();
+        iter.into_iter().for_each(|direction| {
+            directions.insert(direction);
+        });
+        directions
+    }
+}
+
+pub trait Optic: fmt::Debug + Send + Sync {
+    // The directions a beam leaves this tile in, given the direction it entered in.
+    fn deflect(&self, direction: Direction) -> Directions;
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Element {
+    Empty,
+    // `/`
+    ForwardMirror,
+    // `\`
+    BackwardMirror,
+    // `-`
+    HorizontalSplitter,
+    // `|`
+    VerticalSplitter,
+    // Stops every beam that enters it
+    Absorber,
+}
+
+impl Optic for Element {
+    fn deflect(&self, direction: Direction) -> Directions {
+        use Direction::*;
+
+        match (self, direction) {
+            (Self::Absorber, _) => Directions::default(),
+            (Self::Empty, _)
+            | (Self::HorizontalSplitter, East | West)
+            | (Self::VerticalSplitter, North | South) => direction.into(),
+            (Self::ForwardMirror, North) | (Self::BackwardMirror, South) => East.into(),
+            (Self::ForwardMirror, South) | (Self::BackwardMirror, North) => West.into(),
+            (Self::ForwardMirror, East) | (Self::BackwardMirror, West) => North.into(),
+            (Self::ForwardMirror, West) | (Self::BackwardMirror, East) => South.into(),
+            (Self::HorizontalSplitter, _) => [East, West].into_iter().collect(),
+            (Self::VerticalSplitter, _) => [North, South].into_iter().collect(),
+        }
+    }
+}
+
+// A mirror that beams travelling in the `open` direction pass straight through.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct OneWayMirror {
+    pub mirror: Element,
+    pub open: Direction,
+}
+
+impl Optic for OneWayMirror {
+    fn deflect(&self, direction: Direction) -> Directions {
+        if direction == self.open {
+            direction.into()
+        } else {
+            self.mirror.deflect(direction)
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Optics {
+    optics: HashMap<char, Arc<dyn Optic>>,
+}
+
+impl Default for Optics {
+    fn default() -> Self {
+        Self::empty()
+            .with('.', Element::Empty)
+            .with('/', Element::ForwardMirror)
+            .with('\\', Element::BackwardMirror)
+            .with('-', Element::HorizontalSplitter)
+            .with('|', Element::VerticalSplitter)
+    }
+}
+
+impl Optics {
+    pub fn empty() -> Self {
+        Self {
+            optics: HashMap::new(),
+        }
+    }
+
+    pub fn with(mut self, symbol: char, optic: impl Optic + 'static) -> Self {
+        self.register(symbol, optic);
+        self
+    }
+
+    pub fn register(&mut self, symbol: char, optic: impl Optic + 'static) {
+        self.optics.insert(symbol, Arc::new(optic));
+    }
+
+    pub fn get(&self, symbol: char) -> Option<&dyn Optic> {
+        self.optics.get(&symbol).map(|optic| optic.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directions() {
+        let mut directions = Directions::from(Direction::West);
+        assert!(directions.insert(Direction::North));
+        assert!(!directions.insert(Direction::West));
+
+        assert_eq!(directions.len(), 2);
+        assert_eq!(
+            directions.iter().collect::<Vec<_>>(),
+            vec![Direction::North, Direction::West]
+        );
+    }
+
+    #[test]
+    fn test_one_way_mirror() {
+        let optic = OneWayMirror {
+            mirror: Element::ForwardMirror,
+            open: Direction::East,
+        };
+
+        assert_eq!(optic.deflect(Direction::East), Direction::East.into());
+        assert_eq!(optic.deflect(Direction::West), Direction::South.into());
+    }
+}
@@ -0,0 +1,279 @@
+use grid::Grid;
+use helpers::search::{self, SearchSpace};
+
+type Point = (usize, usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    South,
+    East,
+    West,
+}
+
+impl Direction {
+    const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::South,
+        Direction::East,
+        Direction::West,
+    ];
+
+    fn turns(&self) -> [Direction; 2] {
+        match self {
+            Self::North | Self::South => [Self::East, Self::West],
+            Self::East | Self::West => [Self::North, Self::South],
+        }
+    }
+
+    fn advance(&self, (row, col): Point, (rows, cols): (usize, usize)) -> Option<Point> {
+        match self {
+            Self::North => row.checked_sub(1).map(|row| (row, col)),
+            Self::South => (row + 1 < rows).then_some((row + 1, col)),
+            Self::East => (col + 1 < cols).then_some((row, col + 1)),
+            Self::West => col.checked_sub(1).map(|col| (row, col)),
+        }
+    }
+
+    fn arrow(&self) -> char {
+        match self {
+            Self::North => '^',
+            Self::South => 'v',
+            Self::East => '>',
+            Self::West => '<',
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Crucible {
+    // The blocks a crucible must move in a straight line before it can turn or stop
+    pub min_straight: usize,
+    pub max_straight: usize,
+}
+
+impl Crucible {
+    pub const NORMAL: Crucible = Crucible {
+        min_straight: 1,
+        max_straight: 3,
+    };
+
+    pub const ULTRA: Crucible = Crucible {
+        min_straight: 4,
+        max_straight: 10,
+    };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Step {
+    pub point: Point,
+    // The direction the crucible moved in to enter this block
+    pub direction: Direction,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CruciblePath {
+    pub heat_loss: usize,
+    pub steps: Vec<Step>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct State {
+    point: Point,
+    direction: Direction,
+    straight_for: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeatMap {
+    blocks: Grid<u8>,
+}
+
+// Every (block, direction, straight run) state a crucible can be in, numbered densely.
+struct CrucibleSpace<'a> {
+    map: &'a HeatMap,
+    crucible: &'a Crucible,
+}
+
+impl SearchSpace for CrucibleSpace<'_> {
+    type State = State;
+
+    fn state_count(&self) -> usize {
+        self.map.blocks.rows() * self.map.blocks.cols() * 4 * (self.crucible.max_straight + 1)
+    }
+
+    fn index(&self, state: State) -> usize {
+        let point = state.point.0 * self.map.blocks.cols() + state.point.1;
+        (point * 4 + state.direction as usize) * (self.crucible.max_straight + 1)
+            + state.straight_for
+    }
+
+    fn state(&self, index: usize) -> State {
+        let cols = self.map.blocks.cols();
+        let (rest, straight_for) = (
+            index / (self.crucible.max_straight + 1),
+            index % (self.crucible.max_straight + 1),
+        );
+        let (point, direction) = (rest / 4, rest % 4);
+
+        State {
+            point: (point / cols, point % cols),
+            direction: Direction::ALL[direction],
+            straight_for,
+        }
+    }
+
+    fn successors(&self, state: State, successors: &mut Vec<(State, usize)>) {
+        let straight = (state.straight_for < self.crucible.max_straight)
+            .then_some((state.direction, state.straight_for + 1));
+        let turns = (state.straight_for >= self.crucible.min_straight)
+            .then(|| state.direction.turns().map(|direction| (direction, 1)))
+            .into_iter()
+            .flatten();
+
+        successors.extend(straight.into_iter().chain(turns).filter_map(
+            |(direction, straight_for)| {
+                direction
+                    .advance(state.point, self.map.blocks.size())
+                    .map(|point| {
+                        let state = State {
+                            point,
+                            direction,
+                            straight_for,
+                        };
+                        (state, self.map.blocks[point] as usize)
+                    })
+            },
+        ));
+    }
+
+    fn max_weight(&self) -> Option<usize> {
+        Some(9)
+    }
+}
+
+impl HeatMap {
+    pub fn find_path(&self, crucible: &Crucible) -> Option<CruciblePath> {
+        let (rows, cols) = self.blocks.size();
+        let end = (rows.checked_sub(1)?, cols.checked_sub(1)?);
+        let starts = [Direction::East, Direction::South].map(|direction| State {
+            point: (0, 0),
+            direction,
+            straight_for: 0,
+        });
+
+        let path = search::dijkstra(
+            &CrucibleSpace {
+                map: self,
+                crucible,
+            },
+            starts,
+            |state| state.point == end && state.straight_for >= crucible.min_straight,
+        )
+        .path?;
+
+        Some(CruciblePath {
+            heat_loss: path.cost,
+            steps: path
+                .states
+                .into_iter()
+                .filter(|state| state.straight_for > 0)
+                .map(|state| Step {
+                    point: state.point,
+                    direction: state.direction,
+                })
+                .collect(),
+        })
+    }
+
+    pub fn render(&self, path: &CruciblePath) -> String {
+        let mut rendered = Grid::from_vec(
+            self.blocks
+                .iter()
+                .map(|&block| char::from_digit(block as u32, 10).unwrap_or('?'))
+                .collect(),
+            self.blocks.cols(),
+        );
+
+        for step in &path.steps {
+            rendered[step.point] = step.direction.arrow();
+        }
+
+        rendered
+            .iter_rows()
+            .map(|row| row.collect::<String>() + "\n")
+            .collect()
+    }
+}
+
+impl From<&str> for HeatMap {
+    fn from(input: &str) -> Self {
+        Self {
+            blocks: Grid::from_vec(
+                input
+                    .lines()
+                    .flat_map(|line| {
+                        line.chars()
+                            .filter_map(|heat_loss| heat_loss.to_digit(10))
+                            .map(|heat_loss| heat_loss as u8)
+                    })
+                    .collect(),
+                input.lines().next().map_or(0, |row| row.len()),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_valid(map: &HeatMap, crucible: &Crucible, path: &CruciblePath) {
+        let heat_loss = path
+            .steps
+            .iter()
+            .map(|step| map.blocks[step.point] as usize)
+            .sum::<usize>();
+        assert_eq!(heat_loss, path.heat_loss);
+
+        let runs = path
+            .steps
+            .chunk_by(|a, b| a.direction == b.direction)
+            .map(|run| run.len())
+            .collect::<Vec<_>>();
+        assert!(runs
+            .iter()
+            .all(|run| (crucible.min_straight..=crucible.max_straight).contains(run)));
+    }
+
+    #[test]
+    fn test_find_path() {
+        let map = HeatMap::from(include_str!("../data/example.txt"));
+
+        for (crucible, expected) in [(Crucible::NORMAL, 102), (Crucible::ULTRA, 94)] {
+            let path = map.find_path(&crucible).unwrap();
+            assert_eq!(path.heat_loss, expected);
+            assert_valid(&map, &crucible, &path);
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let map = HeatMap::from(include_str!("../data/example2.txt"));
+        let path = map.find_path(&Crucible::ULTRA).unwrap();
+
+        assert_eq!(path.heat_loss, 71);
+        assert_eq!(
+            map.render(&path),
+            [
+                "1>>>>>>>1111",
+                "9999999v9991",
+                "9999999v9991",
+                "9999999v9991",
+                "9999999v>>>>",
+            ]
+            .join("\n")
+                + "\n"
+        );
+    }
+}
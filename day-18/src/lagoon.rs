@@ -0,0 +1,380 @@
+use std::fmt;
+
+use grid::Grid;
+use nom::{
+    bytes::complete::{tag, take_while_m_n},
+    character::complete::{self, one_of, space1},
+    combinator::all_consuming,
+    sequence::{delimited, terminated, tuple},
+    IResult,
+};
+
+type Point = (i64, i64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    fn offset(&self) -> Point {
+        match self {
+            Self::Up => (-1, 0),
+            Self::Down => (1, 0),
+            Self::Left => (0, -1),
+            Self::Right => (0, 1),
+        }
+    }
+
+    fn opposite(&self) -> Self {
+        match self {
+            Self::Up => Self::Down,
+            Self::Down => Self::Up,
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
+}
+
+impl From<char> for Direction {
+    fn from(c: char) -> Self {
+        match c {
+            'U' | '3' => Self::Up,
+            'D' | '1' => Self::Down,
+            'L' | '2' => Self::Left,
+            'R' | '0' => Self::Right,
+            _ => unreachable!("Must be a direction"),
+        }
+    }
+}
+
+// How a line of the dig plan turns into a step: part one reads the direction and
+// meters, part two reads them out of the colour code instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Decoder {
+    Plain,
+    Hex,
+}
+
+impl Decoder {
+    fn parse(input: &str) -> IResult<&str, (char, i64, &str, char)> {
+        tuple((
+            terminated(one_of("UDLR"), space1),
+            terminated(complete::i64, space1),
+            delimited(
+                tag("(#"),
+                tuple((
+                    take_while_m_n(5, 5, |c: char| c.is_ascii_hexdigit()),
+                    one_of("0123"),
+                )),
+                tag(")"),
+            ),
+        ))(input)
+        .map(|(rest, (direction, meters, (hex_meters, hex_direction)))| {
+            (rest, (direction, meters, hex_meters, hex_direction))
+        })
+    }
+
+    pub fn decode(&self, line: &str) -> (Direction, i64) {
+        let (_, (direction, meters, hex_meters, hex_direction)) =
+            all_consuming(Self::parse)(line.trim_end()).expect("Must match the parser");
+
+        match self {
+            Self::Plain => (direction.into(), meters),
+            Self::Hex => (
+                hex_direction.into(),
+                i64::from_str_radix(hex_meters, 16).expect("Must be hexadecimal"),
+            ),
+        }
+    }
+
+    pub fn polygon(&self, input: &str) -> Result<RectilinearPolygon, OpenPlan> {
+        RectilinearPolygon::new(
+            input
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| self.decode(line)),
+        )
+    }
+}
+
+// A dig plan that stops short of where it started, so the trench never closes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OpenPlan {
+    pub end: Point,
+}
+
+impl fmt::Display for OpenPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "dig plan ends at {:?} instead of returning to (0, 0)",
+            self.end
+        )
+    }
+}
+
+impl std::error::Error for OpenPlan {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RectilinearPolygon {
+    steps: Vec<(Direction, i64)>,
+    // The corner at the start of each step, beginning at the origin
+    vertices: Vec<Point>,
+}
+
+impl RectilinearPolygon {
+    pub fn new(steps: impl IntoIterator<Item = (Direction, i64)>) -> Result<Self, OpenPlan> {
+        let steps: Vec<_> = steps.into_iter().collect();
+        let mut vertices = vec![(0, 0)];
+
+        for &(direction, meters) in &steps {
+            let (row, col) = vertices[vertices.len() - 1];
+            let (d_row, d_col) = direction.offset();
+            vertices.push((row + d_row * meters, col + d_col * meters));
+        }
+
+        let end = vertices.pop().expect("Must start at the origin");
+        if end != (0, 0) {
+            return Err(OpenPlan { end });
+        }
+
+        Ok(Self { steps, vertices })
+    }
+
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    fn segments(&self) -> impl Iterator<Item = (Point, Point)> + '_ {
+        self.vertices
+            .iter()
+            .zip(self.vertices.iter().cycle().skip(1))
+            .map(|(&a, &b)| (a, b))
+    }
+
+    // The trench length, which is also the number of lattice points on the boundary.
+    pub fn perimeter(&self) -> i64 {
+        self.steps.iter().map(|(_, meters)| meters).sum()
+    }
+
+    pub fn boundary_points(&self) -> i64 {
+        self.perimeter()
+    }
+
+    // See https://en.wikipedia.org/wiki/Shoelace_formula
+    pub fn area(&self) -> i64 {
+        self.segments()
+            .map(|(a, b)| a.1 * b.0 - b.1 * a.0)
+            .sum::<i64>()
+            .abs()
+            / 2
+    }
+
+    // See https://en.wikipedia.org/wiki/Pick%27s_theorem
+    pub fn interior_points(&self) -> i64 {
+        self.area() - self.boundary_points() / 2 + 1
+    }
+
+    // The lagoon is one meter deep, so every dug square holds one cubic meter.
+    pub fn cubic_meters(&self) -> i64 {
+        self.interior_points() + self.boundary_points()
+    }
+
+    // Pairs of steps whose trenches cross or overlap, other than neighbours meeting at
+    // their shared corner. Area and Pick's theorem only hold when this is empty.
+    pub fn self_intersections(&self) -> Vec<(usize, usize)> {
+        let segments: Vec<_> = self
+            .segments()
+            .map(|(a, b)| ((a.0.min(b.0), a.1.min(b.1)), (a.0.max(b.0), a.1.max(b.1))))
+            .collect();
+        let count = segments.len();
+        let mut intersections = Vec::new();
+
+        for i in 0..count {
+            for j in i + 1..count {
+                let adjacent = j == i + 1 || (i == 0 && j == count - 1);
+                let intersects = if adjacent {
+                    let (first, second) = if j == i + 1 { (i, j) } else { (j, i) };
+                    self.steps[first].0.opposite() == self.steps[second].0
+                } else {
+                    let ((a_min, a_max), (b_min, b_max)) = (segments[i], segments[j]);
+                    a_min.0 <= b_max.0
+                        && b_min.0 <= a_max.0
+                        && a_min.1 <= b_max.1
+                        && b_min.1 <= a_max.1
+                };
+
+                if intersects {
+                    intersections.push((i, j));
+                }
+            }
+        }
+
+        intersections
+    }
+
+    pub fn is_simple(&self) -> bool {
+        self.self_intersections().is_empty()
+    }
+
+    // The top left and bottom right corners of the smallest rectangle around the trench.
+    pub fn bounds(&self) -> (Point, Point) {
+        self.vertices.iter().fold(
+            ((i64::MAX, i64::MAX), (i64::MIN, i64::MIN)),
+            |(min, max), &(row, col)| {
+                (
+                    (min.0.min(row), min.1.min(col)),
+                    (max.0.max(row), max.1.max(col)),
+                )
+            },
+        )
+    }
+
+    // Draws the trench as `#`, and the interior too when it has been dug out. Only
+    // sensible for plans as small as part one's.
+    pub fn rasterize(&self, dig_interior: bool) -> Grid<char> {
+        let ((min_row, min_col), (max_row, max_col)) = self.bounds();
+        let mut grid = Grid::init(
+            (max_row - min_row + 1) as usize,
+            (max_col - min_col + 1) as usize,
+            '.',
+        );
+
+        for ((a_row, a_col), (b_row, b_col)) in self.segments() {
+            for row in a_row.min(b_row)..=a_row.max(b_row) {
+                for col in a_col.min(b_col)..=a_col.max(b_col) {
+                    grid[((row - min_row) as usize, (col - min_col) as usize)] = '#';
+                }
+            }
+        }
+
+        if dig_interior {
+            // Scan each row left to right, flipping inside and outside at every vertical
+            // trench, counting a trench on the lower of its two rows only.
+            let verticals: Vec<_> = self
+                .segments()
+                .filter(|(a, b)| a.1 == b.1)
+                .map(|(a, b)| (a.1, a.0.min(b.0), a.0.max(b.0)))
+                .collect();
+
+            for row in min_row..=max_row {
+                for col in min_col..=max_col {
+                    let point = ((row - min_row) as usize, (col - min_col) as usize);
+                    let crossings = verticals
+                        .iter()
+                        .filter(|&&(x, top, bottom)| x < col && top <= row && row < bottom)
+                        .count();
+
+                    if crossings % 2 == 1 {
+                        grid[point] = '#';
+                    }
+                }
+            }
+        }
+
+        grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(grid: &Grid<char>) -> String {
+        grid.iter_rows()
+            .map(|row| row.collect::<String>() + "\n")
+            .collect()
+    }
+
+    #[test]
+    fn test_decoder() {
+        let line = "R 6 (#70c710)";
+
+        assert_eq!(Decoder::Plain.decode(line), (Direction::Right, 6));
+        assert_eq!(Decoder::Hex.decode(line), (Direction::Right, 461937));
+        assert_eq!(
+            Decoder::Hex.decode("U 2 (#caa171)"),
+            (Direction::Down, 829975)
+        );
+    }
+
+    #[test]
+    fn test_polygon() {
+        let input = include_str!("../data/example.txt");
+        let polygon = Decoder::Plain.polygon(input).unwrap();
+
+        assert_eq!(polygon.boundary_points(), 38);
+        assert_eq!(polygon.area(), 42);
+        assert_eq!(polygon.interior_points(), 24);
+        assert_eq!(polygon.cubic_meters(), 62);
+        assert!(polygon.is_simple());
+        assert_eq!(
+            Decoder::Hex.polygon(input).unwrap().cubic_meters(),
+            952408144115
+        );
+    }
+
+    #[test]
+    fn test_open_plan() {
+        use Direction::*;
+
+        assert_eq!(
+            RectilinearPolygon::new([(Right, 2), (Down, 2), (Left, 2)]),
+            Err(OpenPlan { end: (2, 0) })
+        );
+        assert_eq!(
+            Decoder::Plain.polygon("R 6 (#70c710)\nD 5 (#0dc571)\n"),
+            Err(OpenPlan { end: (5, 6) })
+        );
+        assert!(RectilinearPolygon::new([]).is_ok());
+    }
+
+    #[test]
+    fn test_self_intersections() {
+        use Direction::*;
+
+        let crossed = RectilinearPolygon::new([
+            (Right, 2),
+            (Down, 2),
+            (Left, 1),
+            (Up, 3),
+            (Left, 1),
+            (Down, 1),
+        ])
+        .unwrap();
+        assert_eq!(crossed.self_intersections(), vec![(0, 3)]);
+
+        let doubled_back = RectilinearPolygon::new([(Right, 2), (Left, 2)]).unwrap();
+        assert_eq!(doubled_back.self_intersections(), vec![(0, 1)]);
+    }
+
+    #[test]
+    fn test_rasterize() {
+        let polygon = Decoder::Plain
+            .polygon(include_str!("../data/example.txt"))
+            .unwrap();
+
+        assert_eq!(
+            render(&polygon.rasterize(false)),
+            [
+                "#######", "#.....#", "###...#", "..#...#", "..#...#", "###.###", "#...#..",
+                "##..###", ".#....#", ".######",
+            ]
+            .join("\n")
+                + "\n"
+        );
+        assert_eq!(
+            render(&polygon.rasterize(true)),
+            [
+                "#######", "#######", "#######", "..#####", "..#####", "#######", "#####..",
+                "#######", ".######", ".######",
+            ]
+            .join("\n")
+                + "\n"
+        );
+    }
+}
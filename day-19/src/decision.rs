@@ -0,0 +1,319 @@
+use std::{collections::HashMap, fmt};
+
+use crate::workflow::{Category, Condition, Destination, Part, Rating, Ratings, Rule, Workflow};
+
+// Every rating a part can have, one range per `Category`.
+pub const ALL_PARTS: Ratings = [1..4001, 1..4001, 1..4001, 1..4001];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CompileError {
+    // A workflow sends parts to one that isn't defined
+    Undefined {
+        name: String,
+        referenced_by: Option<String>,
+    },
+    // The workflows, in order, that send parts back round to the first one
+    Cycle(Vec<String>),
+    // A workflow whose last rule is a test, leaving nowhere for other parts to go
+    MissingDefault(String),
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Undefined {
+                name,
+                referenced_by: Some(referenced_by),
+            } => write!(
+                f,
+                "workflow '{name}' referenced by '{referenced_by}' is undefined"
+            ),
+            Self::Undefined { name, .. } => write!(f, "workflow '{name}' is undefined"),
+            Self::Cycle(names) => write!(f, "workflows form a cycle: {}", names.join(" -> ")),
+            Self::MissingDefault(name) => write!(f, "workflow '{name}' has no default rule"),
+        }
+    }
+}
+
+impl std::error::Error for CompileError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Node {
+    Accept,
+    Reject,
+    // Parts rated below the threshold in the category go to `below`, the rest to `above`
+    Split {
+        category: Category,
+        threshold: usize,
+        below: usize,
+        above: usize,
+    },
+}
+
+// The workflows flattened into a single graph of threshold tests. Nodes can be shared,
+// but every node only refers to nodes before it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DecisionTree {
+    nodes: Vec<Node>,
+    root: usize,
+}
+
+const ACCEPT: usize = 0;
+const REJECT: usize = 1;
+
+struct Compiler<'w, 'a> {
+    workflows: &'w HashMap<&'a str, Workflow<'a>>,
+    nodes: Vec<Node>,
+    compiled: HashMap<&'a str, usize>,
+    // The workflows currently being compiled, to spot cycles
+    path: Vec<&'a str>,
+}
+
+impl<'a> Compiler<'_, 'a> {
+    fn split(&mut self, category: Category, threshold: usize, below: usize, above: usize) -> usize {
+        if below == above {
+            return below;
+        }
+
+        self.nodes.push(Node::Split {
+            category,
+            threshold,
+            below,
+            above,
+        });
+        self.nodes.len() - 1
+    }
+
+    fn test(&mut self, rating: &Rating, matched: usize, otherwise: usize) -> usize {
+        let Rating {
+            category, value, ..
+        } = *rating;
+
+        match rating.condition {
+            Condition::Less => self.split(category, value, matched, otherwise),
+            Condition::Greater => self.split(category, value + 1, otherwise, matched),
+            Condition::Equal => {
+                let at_or_above = self.split(category, value + 1, matched, otherwise);
+                self.split(category, value, otherwise, at_or_above)
+            }
+        }
+    }
+
+    fn destination(&mut self, destination: &Destination<'a>) -> Result<usize, CompileError> {
+        match destination {
+            Destination::Accepted => Ok(ACCEPT),
+            Destination::Rejected => Ok(REJECT),
+            Destination::Workflow(name) => self.workflow(name),
+        }
+    }
+
+    fn workflow(&mut self, name: &'a str) -> Result<usize, CompileError> {
+        if let Some(&node) = self.compiled.get(name) {
+            return Ok(node);
+        }
+
+        if let Some(start) = self.path.iter().position(|&other| other == name) {
+            let mut cycle: Vec<_> = self.path[start..].iter().map(|n| n.to_string()).collect();
+            cycle.push(name.to_string());
+            return Err(CompileError::Cycle(cycle));
+        }
+
+        let workflow = self
+            .workflows
+            .get(name)
+            .ok_or_else(|| CompileError::Undefined {
+                name: name.to_string(),
+                referenced_by: self.path.last().map(|n| n.to_string()),
+            })?;
+
+        self.path.push(name);
+
+        // Build from the default rule backwards, so each test knows where failures go.
+        let mut node = None;
+        for rule in workflow.rules.iter().rev() {
+            node = Some(match (rule, node) {
+                (Rule::Default(destination), _) => self.destination(destination)?,
+                (Rule::Test(rating, destination), Some(otherwise)) => {
+                    let matched = self.destination(destination)?;
+                    self.test(rating, matched, otherwise)
+                }
+                (Rule::Test(..), None) => {
+                    return Err(CompileError::MissingDefault(name.to_string()))
+                }
+            });
+        }
+        let node = node.ok_or_else(|| CompileError::MissingDefault(name.to_string()))?;
+
+        self.path.pop();
+        self.compiled.insert(name, node);
+        Ok(node)
+    }
+}
+
+impl DecisionTree {
+    pub fn compile<'a>(
+        workflows: &HashMap<&'a str, Workflow<'a>>,
+        start: &'a str,
+    ) -> Result<Self, CompileError> {
+        let mut compiler = Compiler {
+            workflows,
+            nodes: vec![Node::Accept, Node::Reject],
+            compiled: HashMap::new(),
+            path: Vec::new(),
+        };
+        let root = compiler.workflow(start)?;
+
+        Ok(Self {
+            nodes: compiler.nodes,
+            root,
+        })
+    }
+
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    pub fn root(&self) -> usize {
+        self.root
+    }
+
+    pub fn accepts(&self, part: &Part) -> bool {
+        let mut node = self.root;
+
+        loop {
+            match self.nodes[node] {
+                Node::Accept => return true,
+                Node::Reject => return false,
+                Node::Split {
+                    category,
+                    threshold,
+                    below,
+                    above,
+                } => {
+                    node = if part.ratings[category as usize] < threshold {
+                        below
+                    } else {
+                        above
+                    };
+                }
+            }
+        }
+    }
+
+    // The disjoint boxes of ratings, within `ranges`, that end up accepted.
+    pub fn accepted_ranges(&self, ranges: Ratings) -> Vec<Ratings> {
+        let mut accepted = Vec::new();
+        let mut stack = vec![(self.root, ranges)];
+
+        while let Some((node, ranges)) = stack.pop() {
+            match self.nodes[node] {
+                Node::Accept => accepted.push(ranges),
+                Node::Reject => (),
+                Node::Split {
+                    category,
+                    threshold,
+                    below,
+                    above,
+                } => {
+                    let range = &ranges[category as usize];
+                    let split = threshold.clamp(range.start, range.end.max(range.start));
+
+                    for (node, range) in [(below, range.start..split), (above, split..range.end)] {
+                        if !range.is_empty() {
+                            let mut ranges = ranges.clone();
+                            ranges[category as usize] = range;
+                            stack.push((node, ranges));
+                        }
+                    }
+                }
+            }
+        }
+
+        accepted
+    }
+
+    pub fn count_accepted(&self, ranges: Ratings) -> usize {
+        self.accepted_ranges(ranges)
+            .iter()
+            .map(|ranges| ranges.iter().map(|range| range.len()).product::<usize>())
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::parse;
+
+    fn with_parts(workflows: &str) -> String {
+        format!("{workflows}\n\n{{x=1,m=2,a=3,s=4}}")
+    }
+
+    #[test]
+    fn test_compile() {
+        let (workflows, parts) = parse(include_str!("../data/example.txt"));
+        let tree = DecisionTree::compile(&workflows, "in").unwrap();
+
+        let accepted = parts.iter().filter(|part| tree.accepts(part)).count();
+        assert_eq!(accepted, 3);
+        assert_eq!(tree.count_accepted(ALL_PARTS), 167409079868000);
+
+        let ranges = [1400..1430, 830..850, 1710..1720, 1340..1360];
+        let brute_force = ranges[0]
+            .clone()
+            .flat_map(|x| ranges[1].clone().map(move |m| (x, m)))
+            .flat_map(|(x, m)| ranges[2].clone().map(move |a| (x, m, a)))
+            .flat_map(|(x, m, a)| ranges[3].clone().map(move |s| [x, m, a, s]))
+            .filter(|&ratings| tree.accepts(&Part { ratings }))
+            .count();
+        assert_eq!(tree.count_accepted(ranges), brute_force);
+    }
+
+    #[test]
+    fn test_equal_condition() {
+        let input = with_parts("in{x=5:A,m=7:R,A}");
+        let (workflows, _) = parse(&input);
+        let tree = DecisionTree::compile(&workflows, "in").unwrap();
+
+        assert!(tree.accepts(&Part {
+            ratings: [5, 7, 1, 1]
+        }));
+        assert!(!tree.accepts(&Part {
+            ratings: [4, 7, 1, 1]
+        }));
+        assert!(tree.accepts(&Part {
+            ratings: [6, 8, 1, 1]
+        }));
+        assert_eq!(tree.count_accepted([1..11, 1..11, 1..2, 1..2]), 100 - 9);
+        assert_eq!(tree.count_accepted([5..6, 7..8, 1..2, 1..2]), 1);
+    }
+
+    #[test]
+    fn test_compile_errors() {
+        let input = with_parts("in{x<5:a,R}\na{m>3:b,A}\nb{a<2:in,R}");
+        let (workflows, _) = parse(&input);
+        assert_eq!(
+            DecisionTree::compile(&workflows, "in"),
+            Err(CompileError::Cycle(
+                ["in", "a", "b", "in"].map(String::from).to_vec()
+            ))
+        );
+
+        let input = with_parts("in{x<5:a,R}\na{m>3:c,A}");
+        let (workflows, _) = parse(&input);
+        assert_eq!(
+            DecisionTree::compile(&workflows, "in"),
+            Err(CompileError::Undefined {
+                name: "c".to_string(),
+                referenced_by: Some("a".to_string()),
+            })
+        );
+
+        let input = with_parts("in{x<5:A,m>3:R}");
+        let (workflows, _) = parse(&input);
+        assert_eq!(
+            DecisionTree::compile(&workflows, "in"),
+            Err(CompileError::MissingDefault("in".to_string()))
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use std::{collections::HashMap, fmt::Write};
+
+use crate::{
+    decision::{CompileError, DecisionTree, ALL_PARTS},
+    workflow::{Destination, Ratings, Rule, Workflow},
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Volume {
+    // The number of distinct parts that pass through a workflow
+    pub reached: usize,
+    // How many of those end up accepted
+    pub accepted: usize,
+}
+
+fn size(ranges: &Ratings) -> usize {
+    ranges.iter().map(|range| range.len()).product()
+}
+
+fn walk<'a>(
+    workflows: &HashMap<&'a str, Workflow<'a>>,
+    name: &'a str,
+    ranges: Ratings,
+    volumes: &mut HashMap<&'a str, Volume>,
+) -> usize {
+    let reached = size(&ranges);
+    let mut accepted = 0;
+    let mut remaining = vec![ranges];
+
+    for rule in &workflows[name].rules {
+        let (passed, destination) = match rule {
+            Rule::Test(rating, destination) => {
+                let mut passed = Vec::new();
+                let mut failed = Vec::new();
+                for ranges in &remaining {
+                    let (pass, fail) = rating.split(ranges);
+                    passed.extend(pass);
+                    failed.extend(fail);
+                }
+
+                remaining = failed;
+                (passed, destination)
+            }
+            Rule::Default(destination) => (std::mem::take(&mut remaining), destination),
+        };
+
+        for ranges in passed {
+            accepted += match destination {
+                Destination::Accepted => size(&ranges),
+                Destination::Rejected => 0,
+                Destination::Workflow(next) => walk(workflows, next, ranges, volumes),
+            };
+        }
+    }
+
+    let volume = volumes.entry(name).or_default();
+    volume.reached += reached;
+    volume.accepted += accepted;
+    accepted
+}
+
+// How many parts reach each workflow from `start`, and how many of them are accepted.
+pub fn volumes<'a>(
+    workflows: &HashMap<&'a str, Workflow<'a>>,
+    start: &'a str,
+) -> Result<HashMap<&'a str, Volume>, CompileError> {
+    // Compiling checks there are no cycles or missing workflows to trip the walk up.
+    DecisionTree::compile(workflows, start)?;
+
+    let mut volumes = HashMap::new();
+    walk(workflows, start, ALL_PARTS, &mut volumes);
+    Ok(volumes)
+}
+
+// A Graphviz digraph of the workflows reachable from `start`, labelling each workflow
+// with the parts that reach it and the parts it accepts.
+pub fn to_dot<'a>(
+    workflows: &HashMap<&'a str, Workflow<'a>>,
+    start: &'a str,
+) -> Result<String, CompileError> {
+    let volumes = volumes(workflows, start)?;
+    let mut names: Vec<_> = volumes.keys().copied().collect();
+    names.sort_by_key(|&name| (name != start, name));
+
+    let mut dot = String::from("digraph workflows {\n");
+    writeln!(dot, "    A [shape=doublecircle];").unwrap();
+    writeln!(dot, "    R [shape=circle];").unwrap();
+
+    for name in names {
+        let Volume { reached, accepted } = volumes[name];
+        writeln!(
+            dot,
+            "    {name} [shape=box, label=\"{name}\\naccepts {accepted} of {reached}\"];"
+        )
+        .unwrap();
+
+        for rule in &workflows[name].rules {
+            match rule {
+                Rule::Test(rating, destination) => {
+                    writeln!(dot, "    {name} -> {destination} [label=\"{rating}\"];").unwrap()
+                }
+                Rule::Default(destination) => {
+                    writeln!(dot, "    {name} -> {destination} [style=dashed];").unwrap()
+                }
+            }
+        }
+    }
+
+    dot.push_str("}\n");
+    Ok(dot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{optimize::optimize, workflow::parse};
+
+    #[test]
+    fn test_volumes() {
+        let (workflows, _) = parse(include_str!("../data/example.txt"));
+        let volumes = volumes(&workflows, "in").unwrap();
+
+        assert_eq!(volumes["in"].reached, 4000_usize.pow(4));
+        assert_eq!(volumes["in"].accepted, 167409079868000);
+        assert_eq!(volumes["px"].reached, 4000_usize.pow(3) * 1350);
+        assert_eq!(
+            volumes["lnx"],
+            Volume {
+                reached: volumes["lnx"].reached,
+                accepted: volumes["lnx"].reached
+            }
+        );
+        assert_eq!(volumes["gd"].accepted, 0);
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let input = "in{s<1351:px,A}\npx{a=5:R,A}\n\n{x=1,m=1,a=1,s=1}";
+        let (workflows, _) = parse(input);
+        let total = 4000_usize.pow(4);
+        let px = 4000_usize.pow(3) * 1350;
+
+        assert_eq!(
+            to_dot(&workflows, "in").unwrap(),
+            [
+                "digraph workflows {".to_string(),
+                "    A [shape=doublecircle];".to_string(),
+                "    R [shape=circle];".to_string(),
+                format!(
+                    "    in [shape=box, label=\"in\\naccepts {} of {}\"];",
+                    total - px / 4000,
+                    total
+                ),
+                "    in -> px [label=\"s<1351\"];".to_string(),
+                "    in -> A [style=dashed];".to_string(),
+                format!(
+                    "    px [shape=box, label=\"px\\naccepts {} of {}\"];",
+                    px - px / 4000,
+                    px
+                ),
+                "    px -> R [label=\"a=5\"];".to_string(),
+                "    px -> A [style=dashed];".to_string(),
+                "}\n".to_string(),
+            ]
+            .join("\n")
+        );
+
+        let (workflows, _) = parse(include_str!("../data/example.txt"));
+        let dot = to_dot(&optimize(&workflows, "in"), "in").unwrap();
+        assert!(dot.contains("accepts 167409079868000 of 256000000000000"));
+        assert!(!dot.contains("lnx"));
+    }
+}
@@ -1,2 +1,6 @@
+pub mod decision;
+pub mod dot;
+pub mod optimize;
 pub mod part1;
 pub mod part2;
+pub mod workflow;
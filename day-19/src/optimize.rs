@@ -0,0 +1,240 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    decision::ALL_PARTS,
+    workflow::{Destination, Rule, Workflow},
+};
+
+// Drops tests that no part reaching them can pass, everything after a test that every
+// part passes, and tests that go to the same place as the rules after them.
+fn remove_dead_rules(workflow: &mut Workflow) -> bool {
+    let before = workflow.rules.len();
+    let mut remaining = vec![ALL_PARTS];
+    let mut rules = Vec::new();
+
+    for rule in &workflow.rules {
+        match *rule {
+            Rule::Default(_) => {
+                rules.push(*rule);
+                break;
+            }
+            Rule::Test(rating, destination) => {
+                let mut passed = false;
+                let mut failed = Vec::new();
+                for ranges in &remaining {
+                    let (pass, fail) = rating.split(ranges);
+                    passed |= pass.is_some();
+                    failed.extend(fail);
+                }
+
+                if failed.is_empty() {
+                    rules.push(Rule::Default(destination));
+                    break;
+                }
+                if passed {
+                    rules.push(*rule);
+                }
+                remaining = failed;
+            }
+        }
+    }
+
+    while let [.., Rule::Test(_, test), Rule::Default(default)] = rules[..] {
+        if test != default {
+            break;
+        }
+        rules.remove(rules.len() - 2);
+    }
+
+    workflow.rules = rules;
+    workflow.rules.len() != before
+}
+
+fn destinations<'a, 'w>(
+    workflows: &'w HashMap<&'a str, Workflow<'a>>,
+) -> impl Iterator<Item = (&'a str, &'w Rule<'a>)> {
+    workflows
+        .values()
+        .flat_map(|workflow| workflow.rules.iter().map(|rule| (workflow.name, rule)))
+}
+
+fn retarget<'a>(rule: &mut Rule<'a>, from: &str, to: Destination<'a>) {
+    let (Rule::Test(_, destination) | Rule::Default(destination)) = rule;
+    if *destination == Destination::Workflow(from) {
+        *destination = to;
+    }
+}
+
+fn remove_unreachable(workflows: &mut HashMap<&str, Workflow>, start: &str) -> bool {
+    let mut reachable = HashSet::from([start]);
+    let mut stack = vec![start];
+
+    while let Some(name) = stack.pop() {
+        for rule in workflows.get(name).into_iter().flat_map(|w| &w.rules) {
+            if let Rule::Test(_, Destination::Workflow(next))
+            | Rule::Default(Destination::Workflow(next)) = rule
+            {
+                if reachable.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+    }
+
+    let before = workflows.len();
+    workflows.retain(|name, _| reachable.contains(name));
+    workflows.len() != before
+}
+
+// Replaces a workflow that sends every part to one place with that place. Only one is
+// merged at a time, since its destination may be another uniform workflow that has to be
+// looked up again once this one's references have moved.
+fn merge_uniform<'a>(workflows: &mut HashMap<&'a str, Workflow<'a>>, start: &str) -> bool {
+    let Some((name, destination)) = workflows
+        .values()
+        .filter(|workflow| workflow.name != start)
+        .find_map(|workflow| match workflow.rules[..] {
+            [Rule::Default(destination)] if destination != Destination::Workflow(workflow.name) => {
+                Some((workflow.name, destination))
+            }
+            _ => None,
+        })
+    else {
+        return false;
+    };
+
+    workflows.remove(name);
+    for workflow in workflows.values_mut() {
+        workflow
+            .rules
+            .iter_mut()
+            .for_each(|rule| retarget(rule, name, destination));
+    }
+
+    true
+}
+
+// Splices a workflow that is only used as another workflow's default into its place.
+fn inline_single_use<'a>(workflows: &mut HashMap<&'a str, Workflow<'a>>, start: &str) -> bool {
+    let mut uses: HashMap<&str, Vec<(&str, bool)>> = HashMap::new();
+    for (from, rule) in destinations(workflows) {
+        match rule {
+            Rule::Test(_, Destination::Workflow(to)) => {
+                uses.entry(to).or_default().push((from, false))
+            }
+            Rule::Default(Destination::Workflow(to)) => {
+                uses.entry(to).or_default().push((from, true))
+            }
+            _ => (),
+        }
+    }
+
+    let Some((name, parent)) = uses.into_iter().find_map(|(name, uses)| match uses[..] {
+        [(parent, true)] if name != start && name != parent && workflows.contains_key(name) => {
+            Some((name, parent))
+        }
+        _ => None,
+    }) else {
+        return false;
+    };
+
+    let inlined = workflows.remove(name).expect("Must be a workflow").rules;
+    let parent = workflows.get_mut(parent).expect("Must be a workflow");
+    parent.rules.pop();
+    parent.rules.extend(inlined);
+    true
+}
+
+// Shrinks the workflows reachable from `start` without changing which parts are accepted.
+pub fn optimize<'a>(
+    workflows: &HashMap<&'a str, Workflow<'a>>,
+    start: &str,
+) -> HashMap<&'a str, Workflow<'a>> {
+    let mut workflows = workflows.clone();
+    remove_unreachable(&mut workflows, start);
+
+    loop {
+        let mut changed = false;
+        for workflow in workflows.values_mut() {
+            changed |= remove_dead_rules(workflow);
+        }
+        changed |= merge_uniform(&mut workflows, start);
+        changed |= inline_single_use(&mut workflows, start);
+        changed |= remove_unreachable(&mut workflows, start);
+
+        if !changed {
+            return workflows;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decision::DecisionTree, workflow::parse};
+
+    fn render(workflows: &HashMap<&str, Workflow>) -> Vec<String> {
+        let mut rendered: Vec<_> = workflows.values().map(|w| w.to_string()).collect();
+        rendered.sort();
+        rendered
+    }
+
+    #[test]
+    fn test_optimize() {
+        let (workflows, parts) = parse(include_str!("../data/example.txt"));
+        let optimized = optimize(&workflows, "in");
+
+        assert_eq!(
+            render(&optimized),
+            [
+                "hdj{m>838:A,a>1716:R,A}",
+                "in{s<1351:px,s>2770:A,m<1801:hdj,R}",
+                "px{a<2006:qkq,m>2090:A,s<537:R,x>2440:R,A}",
+                "qkq{x<1416:A,x>2662:A,R}",
+            ]
+        );
+
+        let before = DecisionTree::compile(&workflows, "in").unwrap();
+        let after = DecisionTree::compile(&optimized, "in").unwrap();
+        assert_eq!(
+            after.count_accepted(ALL_PARTS),
+            before.count_accepted(ALL_PARTS)
+        );
+        assert!(parts
+            .iter()
+            .all(|part| before.accepts(part) == after.accepts(part)));
+    }
+
+    #[test]
+    fn test_chained_uniform() {
+        let (workflows, _) = parse("in{x<10:a,R}\na{b}\nb{A}\n\n{x=1,m=1,a=1,s=1}");
+
+        // The merge order follows the map's, so try it with a few different hashers.
+        for _ in 0..50 {
+            let workflows: HashMap<_, _> = workflows.clone().into_iter().collect();
+            let optimized = optimize(&workflows, "in");
+
+            assert_eq!(render(&optimized), ["in{x<10:A,R}"]);
+            assert!(DecisionTree::compile(&optimized, "in").is_ok());
+        }
+    }
+
+    #[test]
+    fn test_dead_rules() {
+        let input = [
+            "in{x<10:a,x<5:R,x>3000:b,x=3000:A,m=2:R,c}",
+            "a{m>1:A,A}",
+            "b{x>20:R,A}",
+            "c{m>5:R,m<6:A,a<2:R,A}",
+            "",
+            "{x=1,m=1,a=1,s=1}",
+        ]
+        .join("\n");
+        let (workflows, _) = parse(&input);
+
+        assert_eq!(
+            render(&optimize(&workflows, "in")),
+            ["b{x>20:R,A}", "in{x<10:A,x>3000:b,x=3000:A,m=2:R,m>5:R,A}",]
+        );
+    }
+}
@@ -0,0 +1,263 @@
+use std::{collections::HashMap, fmt, ops::Range};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Condition {
+    Less,
+    Greater,
+    Equal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Category {
+    X,
+    M,
+    A,
+    S,
+}
+
+impl Category {
+    pub const ALL: [Category; 4] = [Category::X, Category::M, Category::A, Category::S];
+}
+
+// A box of ratings, one range per `Category`
+pub type Ratings = [Range<usize>; 4];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rating {
+    pub category: Category,
+    pub condition: Condition,
+    pub value: usize,
+}
+
+impl Rating {
+    pub fn matches(&self, part: &Part) -> bool {
+        let rating = part.ratings[self.category as usize];
+
+        match self.condition {
+            Condition::Less => rating < self.value,
+            Condition::Greater => rating > self.value,
+            Condition::Equal => rating == self.value,
+        }
+    }
+
+    // Splits a box of ratings into the part that passes this test, if any, and the
+    // boxes that fail it.
+    pub fn split(&self, ranges: &Ratings) -> (Option<Ratings>, Vec<Ratings>) {
+        let Range { start, end } = ranges[self.category as usize];
+        let (value, below) = (self.value, |limit: usize| start..end.min(limit));
+        let above = |limit: usize| start.max(limit)..end;
+
+        let (passed, failed) = match self.condition {
+            Condition::Less => (below(value), vec![above(value)]),
+            Condition::Greater => (above(value + 1), vec![below(value + 1)]),
+            Condition::Equal => (
+                start.max(value)..end.min(value + 1),
+                vec![below(value), above(value + 1)],
+            ),
+        };
+
+        let with = |range: Range<usize>| {
+            let mut ranges = ranges.clone();
+            ranges[self.category as usize] = range;
+            ranges
+        };
+
+        (
+            (!passed.is_empty()).then(|| with(passed)),
+            failed
+                .into_iter()
+                .filter(|range| !range.is_empty())
+                .map(with)
+                .collect(),
+        )
+    }
+}
+
+impl fmt::Display for Rating {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let category = match self.category {
+            Category::X => 'x',
+            Category::M => 'm',
+            Category::A => 'a',
+            Category::S => 's',
+        };
+        let condition = match self.condition {
+            Condition::Less => '<',
+            Condition::Greater => '>',
+            Condition::Equal => '=',
+        };
+
+        write!(f, "{}{}{}", category, condition, self.value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Destination<'a> {
+    Accepted,
+    Rejected,
+    Workflow(&'a str),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rule<'a> {
+    Test(Rating, Destination<'a>),
+    Default(Destination<'a>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Workflow<'a> {
+    pub name: &'a str,
+    pub rules: Vec<Rule<'a>>,
+}
+
+impl fmt::Display for Destination<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Accepted => write!(f, "A"),
+            Self::Rejected => write!(f, "R"),
+            Self::Workflow(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl fmt::Display for Rule<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Test(rating, destination) => write!(f, "{}:{}", rating, destination),
+            Self::Default(destination) => write!(f, "{}", destination),
+        }
+    }
+}
+
+impl fmt::Display for Workflow<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rules = self
+            .rules
+            .iter()
+            .map(|rule| rule.to_string())
+            .collect::<Vec<_>>();
+
+        write!(f, "{}{{{}}}", self.name, rules.join(","))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Part {
+    // Indexed by `Category`
+    pub ratings: [usize; 4],
+}
+
+impl Part {
+    pub fn total_rating(&self) -> usize {
+        self.ratings.iter().sum()
+    }
+}
+
+mod parser {
+    use super::*;
+    use nom::{
+        branch::alt,
+        bytes::complete::tag,
+        character::complete::{self, alpha1, line_ending},
+        combinator::{map, opt, value},
+        multi::{fold_many1, many1, separated_list1},
+        sequence::{delimited, separated_pair, terminated, tuple},
+        IResult, Parser,
+    };
+    use std::collections::HashMap;
+
+    fn condition(input: &str) -> IResult<&str, Condition> {
+        alt((
+            value(Condition::Less, tag("<")),
+            value(Condition::Greater, tag(">")),
+            value(Condition::Equal, tag("=")),
+        ))(input)
+    }
+
+    fn category(input: &str) -> IResult<&str, Category> {
+        alt((
+            value(Category::X, tag("x")),
+            value(Category::M, tag("m")),
+            value(Category::A, tag("a")),
+            value(Category::S, tag("s")),
+        ))(input)
+    }
+
+    fn rating(input: &str) -> IResult<&str, Rating> {
+        map(
+            tuple((category, condition, complete::u64.map(|n| n as usize))),
+            |(category, condition, value)| Rating {
+                category,
+                condition,
+                value,
+            },
+        )(input)
+    }
+
+    fn ratings(input: &str) -> IResult<&str, [usize; 4]> {
+        fold_many1(
+            terminated(rating, opt(tag(","))),
+            || [0; 4],
+            |mut ratings, rating| {
+                ratings[rating.category as usize] = rating.value;
+                ratings
+            },
+        )(input)
+    }
+
+    fn destination(input: &str) -> IResult<&str, Destination<'_>> {
+        alt((
+            value(Destination::Accepted, tag("A")),
+            value(Destination::Rejected, tag("R")),
+            alpha1.map(Destination::Workflow),
+        ))(input)
+    }
+
+    fn rule(input: &str) -> IResult<&str, Rule<'_>> {
+        alt((
+            separated_pair(rating, tag(":"), destination).map(|(r, d)| Rule::Test(r, d)),
+            destination.map(Rule::Default),
+        ))(input)
+    }
+
+    fn rules(input: &str) -> IResult<&str, Vec<Rule<'_>>> {
+        separated_list1(tag(","), rule)(input)
+    }
+
+    fn workflow(input: &str) -> IResult<&str, (&str, Workflow<'_>)> {
+        map(
+            tuple((alpha1, delimited(tag("{"), rules, tag("}")))),
+            |(name, rules)| (name, Workflow { name, rules }),
+        )(input)
+    }
+
+    fn workflows(input: &str) -> IResult<&str, HashMap<&str, Workflow<'_>>> {
+        fold_many1(
+            terminated(workflow, line_ending),
+            HashMap::new,
+            |mut workflows, (name, workflow)| {
+                workflows.insert(name, workflow);
+                workflows
+            },
+        )(input)
+    }
+
+    fn part(input: &str) -> IResult<&str, Part> {
+        map(delimited(tag("{"), ratings, tag("}")), |ratings| Part {
+            ratings,
+        })(input)
+    }
+
+    fn parts(input: &str) -> IResult<&str, Vec<Part>> {
+        separated_list1(line_ending, part)(input)
+    }
+
+    pub fn parse(input: &str) -> IResult<&str, (HashMap<&str, Workflow<'_>>, Vec<Part>)> {
+        separated_pair(workflows, many1(line_ending), parts)(input)
+    }
+}
+
+pub fn parse(input: &str) -> (HashMap<&str, Workflow<'_>>, Vec<Part>) {
+    let (_, system) = parser::parse(input).expect("Input must be parsable");
+    system
+}
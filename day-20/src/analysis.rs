@@ -0,0 +1,461 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+use helpers::cycle::{first_common_step, Period};
+
+use crate::network::{ModuleKind, Pulse, PulseNetwork};
+
+// A chain of flip-flops counting button presses in binary, which its hub conjunction
+// resets every `period` presses.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Counter<'a> {
+    pub hub: &'a str,
+    // Least significant bit first
+    pub bits: Vec<&'a str>,
+    pub period: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Strategy<'a> {
+    // Read straight from the wiring of each counter
+    Counters(Vec<Counter<'a>>),
+    // Found by simulating until every independent subnetwork repeated a state
+    Subnetworks(Vec<Period>),
+    // Seen happening while simulating
+    Simulation,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Solution<'a> {
+    pub presses: usize,
+    pub strategy: Strategy<'a>,
+}
+
+fn inputs_of_kind(network: &PulseNetwork, module: usize, kind: ModuleKind) -> Option<Vec<usize>> {
+    let inputs = network.inputs(module);
+    inputs
+        .iter()
+        .all(|&input| network.kind(input) == kind)
+        .then(|| inputs.to_vec())
+}
+
+// The conjunction sending to `target`, and the modules that feed that conjunction.
+fn feeders(network: &PulseNetwork, target: usize) -> Option<(usize, Vec<usize>)> {
+    match network.inputs(target) {
+        &[feeder] if network.kind(feeder) == ModuleKind::Conjunction => {
+            Some((feeder, network.inputs(feeder).to_vec()))
+        }
+        _ => None,
+    }
+}
+
+fn counter<'a>(network: &PulseNetwork<'a>, hub: usize) -> Option<Counter<'a>> {
+    let broadcaster = network.module("broadcaster")?;
+    let is_flip_flop = |module: usize| network.kind(module) == ModuleKind::FlipFlop;
+
+    let set_bits: HashSet<_> = inputs_of_kind(network, hub, ModuleKind::FlipFlop)?
+        .into_iter()
+        .collect();
+    let resets: HashSet<_> = network.outputs(hub).filter(|&m| is_flip_flop(m)).collect();
+
+    let mut bits = vec![network
+        .outputs(broadcaster)
+        .find(|output| resets.contains(output) || set_bits.contains(output))?];
+    loop {
+        let current = bits[bits.len() - 1];
+        let previous = bits.len().checked_sub(2).map_or(broadcaster, |i| bits[i]);
+
+        if network
+            .outputs(current)
+            .any(|m| m != hub && !is_flip_flop(m))
+            || network
+                .inputs(current)
+                .iter()
+                .any(|&m| m != hub && m != previous)
+        {
+            return None;
+        }
+
+        let mut next = network.outputs(current).filter(|&m| is_flip_flop(m));
+        match (next.next(), next.next()) {
+            (None, _) => break,
+            (Some(next), None) if !bits.contains(&next) => bits.push(next),
+            _ => return None,
+        }
+    }
+
+    // When the count reaches the period, the hub sends a low pulse to every unset bit
+    // and the lowest bit, which carries through and wraps the count back to zero.
+    let expected_resets: HashSet<_> = bits
+        .iter()
+        .enumerate()
+        .filter(|&(i, bit)| i == 0 || !set_bits.contains(bit))
+        .map(|(_, &bit)| bit)
+        .collect();
+    if resets != expected_resets || !set_bits.iter().all(|bit| bits.contains(bit)) {
+        return None;
+    }
+
+    let period = bits
+        .iter()
+        .enumerate()
+        .filter(|(_, bit)| set_bits.contains(bit))
+        .map(|(i, _)| 1 << i)
+        .sum();
+
+    Some(Counter {
+        hub: network.name(hub),
+        bits: bits.iter().map(|&bit| network.name(bit)).collect(),
+        period,
+    })
+}
+
+// Works for networks where `target` is fed by a conjunction of inverters, each inverting
+// the hub of a binary counter. Every counter's hub briefly sends a low pulse on the
+// presses that are multiples of its period, so they all line up on the lowest common
+// multiple.
+pub fn counters<'a>(network: &PulseNetwork<'a>, target: &str) -> Option<Vec<Counter<'a>>> {
+    let (_, inverters) = feeders(network, network.module(target)?)?;
+
+    inverters
+        .iter()
+        .map(|&inverter| match network.inputs(inverter) {
+            &[hub]
+                if network.kind(inverter) == ModuleKind::Conjunction
+                    && network.kind(hub) == ModuleKind::Conjunction
+                    && network
+                        .outputs(hub)
+                        .filter(|&m| m != inverter)
+                        .all(|m| network.kind(m) == ModuleKind::FlipFlop) =>
+            {
+                counter(network, hub)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+// Every module whose pulses can eventually reach `module`, including itself.
+fn upstream(network: &PulseNetwork, module: usize) -> Vec<usize> {
+    let mut seen = HashSet::from([module]);
+    let mut stack = vec![module];
+
+    while let Some(module) = stack.pop() {
+        for &input in network.inputs(module) {
+            if seen.insert(input) {
+                stack.push(input);
+            }
+        }
+    }
+
+    seen.into_iter().collect()
+}
+
+// Presses the button until `target` receives a low pulse, or until each subnetwork
+// feeding the conjunction before `target` has settled into a cycle of states that sends
+// it one high pulse per cycle, taken back by a low pulse before the press is over. Those
+// subnetworks only depend on themselves, so their
+// cycles can be combined without simulating every press. The network is restored to its
+// state beforehand. Otherwise it keeps pressing up to `limit` times, waiting for the low
+// pulse to turn up.
+pub fn simulate<'a>(
+    network: &mut PulseNetwork<'a>,
+    target: &str,
+    limit: usize,
+) -> Option<Solution<'a>> {
+    let target = network.module(target)?;
+    let feeders = feeders(network, target);
+    let subnetworks: Vec<_> = feeders
+        .iter()
+        .flat_map(|(_, inputs)| inputs.iter().map(|&input| upstream(network, input)))
+        .collect();
+
+    let snapshot = network.snapshot();
+    let start = network.presses();
+
+    let reached: Rc<RefCell<Option<usize>>> = Rc::default();
+    let fired: Rc<RefCell<HashMap<&str, Vec<usize>>>> = Rc::default();
+
+    // Both modules come from the network, so subscribing to them always succeeds.
+    let mut subscriptions: Vec<_> = network
+        .subscribe(network.name(target), Pulse::Low, {
+            let reached = Rc::clone(&reached);
+            move |press, _| {
+                reached.borrow_mut().get_or_insert(press - start);
+            }
+        })
+        .into_iter()
+        .collect();
+    if let Some((feeder, _)) = feeders {
+        let fired = Rc::clone(&fired);
+        subscriptions.extend(network.subscribe(
+            network.name(feeder),
+            Pulse::High,
+            move |press, event| {
+                fired
+                    .borrow_mut()
+                    .entry(event.from)
+                    .or_default()
+                    .push(press - start)
+            },
+        ));
+    }
+
+    let key = |network: &PulseNetwork, modules: &[usize]| {
+        modules
+            .iter()
+            .map(|&module| (network.is_on(module), network.memory(module).to_vec()))
+            .collect::<Vec<_>>()
+    };
+    let mut seen: Vec<HashMap<_, usize>> = subnetworks
+        .iter()
+        .map(|modules| HashMap::from([(key(network, modules), 0)]))
+        .collect();
+    let mut periods: Vec<Option<Period>> = vec![None; subnetworks.len()];
+
+    let names: Vec<_> = feeders
+        .iter()
+        .flat_map(|(_, inputs)| inputs.iter().map(|&input| network.name(input)))
+        .collect();
+
+    // Stops looking for cycles once a subnetwork doesn't settle into a shape they can be
+    // combined from, and just keeps pressing the button instead.
+    let mut detecting = !subnetworks.is_empty();
+    let mut solution = None;
+    for press in 1..=limit {
+        network.press_button();
+
+        if let Some(presses) = *reached.borrow() {
+            solution = Some(Solution {
+                presses,
+                strategy: Strategy::Simulation,
+            });
+            break;
+        }
+        if !detecting {
+            continue;
+        }
+
+        // A feeder still remembered as high after a press can line up with the others on
+        // presses where it sent nothing, which the periods of its pulses don't capture.
+        if feeders
+            .as_ref()
+            .is_some_and(|&(feeder, _)| network.memory(feeder).contains(&Pulse::High))
+        {
+            detecting = false;
+            continue;
+        }
+
+        for (i, modules) in subnetworks.iter().enumerate() {
+            if periods[i].is_some() {
+                continue;
+            }
+
+            let Some(previous) = seen[i].insert(key(network, modules), press) else {
+                continue;
+            };
+
+            // The subnetwork repeats the presses after `previous` forever, so it needs
+            // exactly one high pulse in that window to have a single period.
+            let fired = fired.borrow();
+            let fires: Vec<_> = fired
+                .get(names[i])
+                .into_iter()
+                .flatten()
+                .filter(|&&fire| fire > previous && fire <= press)
+                .collect();
+            match fires[..] {
+                [&first] => {
+                    periods[i] = Some(Period {
+                        first,
+                        length: press - previous,
+                    })
+                }
+                _ => {
+                    detecting = false;
+                    break;
+                }
+            }
+        }
+
+        if detecting && periods.iter().all(Option::is_some) {
+            let periods: Vec<_> = periods.iter().flatten().copied().collect();
+            solution = first_common_step(periods.iter().copied()).map(|presses| Solution {
+                presses,
+                strategy: Strategy::Subnetworks(periods),
+            });
+            break;
+        }
+    }
+
+    subscriptions
+        .into_iter()
+        .for_each(|id| network.unsubscribe(id));
+    network.restore(&snapshot);
+    solution
+}
+
+// The fewest presses before `target` receives a low pulse, reading the answer off the
+// wiring when the network is built from binary counters and simulating otherwise.
+pub fn presses_until_low<'a>(
+    network: &mut PulseNetwork<'a>,
+    target: &str,
+    limit: usize,
+) -> Option<Solution<'a>> {
+    match counters(network, target) {
+        Some(counters) if !counters.is_empty() => Some(Solution {
+            presses: first_common_step(counters.iter().map(|counter| Period {
+                first: counter.period,
+                length: counter.period,
+            }))?,
+            strategy: Strategy::Counters(counters),
+        }),
+        _ => simulate(network, target, limit),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Counters of period 0b101 and 0b11, each inverted into the conjunction before rx
+    const COUNTERS: &str = "\
+broadcaster -> a, x
+%a -> b, ha
+%b -> c
+%c -> ha
+&ha -> a, b, ia
+&ia -> out
+%x -> y, hx
+%y -> hx
+&hx -> x, ix
+&ix -> out
+&out -> rx
+";
+
+    #[test]
+    fn test_counters() {
+        let network = PulseNetwork::from(COUNTERS);
+        let counters = counters(&network, "rx").unwrap();
+
+        assert_eq!(
+            counters,
+            vec![
+                Counter {
+                    hub: "ha",
+                    bits: vec!["a", "b", "c"],
+                    period: 5,
+                },
+                Counter {
+                    hub: "hx",
+                    bits: vec!["x", "y"],
+                    period: 3,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_presses_until_low() {
+        let mut network = PulseNetwork::from(COUNTERS);
+        let solution = presses_until_low(&mut network, "rx", 1000).unwrap();
+        assert_eq!(solution.presses, 15);
+        assert!(matches!(solution.strategy, Strategy::Counters(_)));
+
+        let simulated = simulate(&mut network, "rx", 1000).unwrap();
+        assert_eq!(simulated.presses, 15);
+        assert_eq!(network.presses(), 0);
+
+        let first_low = (1..=15).find(|_| {
+            network
+                .press_button()
+                .iter()
+                .any(|event| event.to == "rx" && event.pulse == Pulse::Low)
+        });
+        assert_eq!(first_low, Some(15));
+    }
+
+    #[test]
+    fn test_simulation_fallback() {
+        // An extra output from a counter bit stops it reading as a binary counter
+        let input = COUNTERS.replace("%c -> ha", "%c -> ha, probe");
+        let mut network = PulseNetwork::from(input.as_str());
+        assert_eq!(counters(&network, "rx"), None);
+
+        let solution = presses_until_low(&mut network, "rx", 1000).unwrap();
+        assert!(matches!(solution.strategy, Strategy::Subnetworks(_)));
+        let first_low = (1..=1000).find(|_| {
+            network
+                .press_button()
+                .iter()
+                .any(|event| event.to == "rx" && event.pulse == Pulse::Low)
+        });
+        assert_eq!(Some(solution.presses), first_low);
+    }
+
+    #[test]
+    fn test_plain_simulation() {
+        // `g` sends out several high pulses every cycle, so its cycle can't be combined
+        // with the counter's and the presses have to be simulated.
+        let input = "\
+broadcaster -> a, p
+%a -> b, ha
+%b -> c
+%c -> ha
+&ha -> a, b, ia
+&ia -> out
+%p -> q, g
+%q -> g
+&g -> out
+&out -> rx
+";
+        let mut network = PulseNetwork::from(input);
+        assert_eq!(counters(&network, "rx"), None);
+
+        let solution = simulate(&mut network, "rx", 1000).unwrap();
+        assert_eq!(solution.strategy, Strategy::Simulation);
+        let first_low = (1..=1000).find(|_| {
+            network
+                .press_button()
+                .iter()
+                .any(|event| event.to == "rx" && event.pulse == Pulse::Low)
+        });
+        assert_eq!(Some(solution.presses), first_low);
+        assert_eq!(simulate(&mut network, "rx", 1), None);
+    }
+
+    #[test]
+    fn test_feeder_held_high() {
+        // `fc` stays high across presses, so `out` can see both its inputs high on a
+        // press where `fc` sends nothing.
+        let input = "\
+broadcaster -> ba, fa
+%ba -> bb, hub
+%bb -> bc, hub
+%bc -> bd
+%bd -> hub
+&hub -> ba, bc, inv
+&inv -> out
+%fa -> fb
+%fb -> fc
+%fc -> out
+&out -> rx
+";
+        let mut network = PulseNetwork::from(input);
+        assert_eq!(counters(&network, "rx"), None);
+
+        let solution = presses_until_low(&mut network, "rx", 1000).unwrap();
+        assert_eq!(solution.strategy, Strategy::Simulation);
+        assert_eq!(solution.presses, 22);
+        let first_low = (1..=1000).find(|_| {
+            network
+                .press_button()
+                .iter()
+                .any(|event| event.to == "rx" && event.pulse == Pulse::Low)
+        });
+        assert_eq!(first_low, Some(22));
+    }
+}
@@ -0,0 +1,78 @@
+use std::fmt::Write;
+
+use crate::network::{ModuleKind, PulseNetwork};
+
+fn shape(kind: ModuleKind) -> &'static str {
+    match kind {
+        ModuleKind::Broadcaster => "house",
+        ModuleKind::FlipFlop => "box",
+        ModuleKind::Conjunction => "diamond",
+        ModuleKind::Untyped => "doublecircle",
+    }
+}
+
+// A Graphviz digraph of the network, shaping each module by its kind so flip-flop chains
+// and the conjunctions they feed stand out.
+pub fn to_dot(network: &PulseNetwork) -> String {
+    let mut dot = String::from("digraph modules {\n");
+    writeln!(dot, "    button [shape=plaintext];").unwrap();
+    writeln!(dot, "    button -> broadcaster;").unwrap();
+
+    for module in 0..network.len() {
+        writeln!(
+            dot,
+            "    {} [shape={}];",
+            network.name(module),
+            shape(network.kind(module))
+        )
+        .unwrap();
+    }
+
+    for module in 0..network.len() {
+        for output in network.outputs(module) {
+            writeln!(
+                dot,
+                "    {} -> {};",
+                network.name(module),
+                network.name(output)
+            )
+            .unwrap();
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_dot() {
+        let network = PulseNetwork::from(include_str!("../data/example2.txt"));
+
+        assert_eq!(
+            to_dot(&network),
+            [
+                "digraph modules {",
+                "    button [shape=plaintext];",
+                "    button -> broadcaster;",
+                "    broadcaster [shape=house];",
+                "    a [shape=box];",
+                "    inv [shape=diamond];",
+                "    con [shape=diamond];",
+                "    b [shape=box];",
+                "    output [shape=doublecircle];",
+                "    broadcaster -> a;",
+                "    a -> inv;",
+                "    a -> con;",
+                "    inv -> b;",
+                "    con -> output;",
+                "    b -> con;",
+                "}\n",
+            ]
+            .join("\n")
+        );
+    }
+}
@@ -1,2 +1,5 @@
+pub mod analysis;
+pub mod dot;
+pub mod network;
 pub mod part1;
 pub mod part2;
@@ -0,0 +1,511 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+};
+
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::alpha1,
+    combinator::{all_consuming, value},
+    multi::separated_list1,
+    sequence::{terminated, tuple},
+    IResult,
+};
+
+const BUTTON: usize = usize::MAX;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Pulse {
+    High,
+    Low,
+}
+
+impl fmt::Display for Pulse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::High => write!(f, "high"),
+            Self::Low => write!(f, "low"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModuleKind {
+    Broadcaster,
+    FlipFlop,
+    Conjunction,
+    // Only ever named as a destination, so it receives pulses but never sends any
+    Untyped,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Event<'a> {
+    pub from: &'a str,
+    pub pulse: Pulse,
+    pub to: &'a str,
+}
+
+impl fmt::Display for Event<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} -{}-> {}", self.from, self.pulse, self.to)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PulseCounts {
+    pub low: usize,
+    pub high: usize,
+}
+
+impl PulseCounts {
+    fn add(&mut self, pulse: Pulse) {
+        match pulse {
+            Pulse::Low => self.low += 1,
+            Pulse::High => self.high += 1,
+        }
+    }
+}
+
+// Everything that changes as the button is pressed, so long runs can be checkpointed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Snapshot {
+    on: Vec<bool>,
+    // The last pulse each conjunction received from each of its inputs, in input order
+    memories: Vec<Vec<Pulse>>,
+    highs: Vec<usize>,
+    counts: PulseCounts,
+    presses: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Warning<'a> {
+    // Named as a destination but never defined, along with the modules sending to it
+    UndefinedTarget {
+        name: &'a str,
+        referenced_by: Vec<&'a str>,
+    },
+    // No pulse from the broadcaster can ever arrive
+    Unreachable(&'a str),
+    // Remembers nothing, so it always sends a low pulse
+    ConjunctionWithoutInputs(&'a str),
+}
+
+impl fmt::Display for Warning<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UndefinedTarget {
+                name,
+                referenced_by,
+            } => write!(
+                f,
+                "module '{}' is sent pulses by {} but never defined",
+                name,
+                referenced_by.join(", ")
+            ),
+            Self::Unreachable(name) => write!(f, "module '{}' is unreachable", name),
+            Self::ConjunctionWithoutInputs(name) => {
+                write!(f, "conjunction '{}' has no inputs", name)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(usize);
+
+type Hook<'a> = Box<dyn FnMut(usize, &Event<'a>) + 'a>;
+
+struct Subscription<'a> {
+    to: usize,
+    pulse: Pulse,
+    hook: Hook<'a>,
+}
+
+pub struct PulseNetwork<'a> {
+    names: Vec<&'a str>,
+    ids: HashMap<&'a str, usize>,
+    kinds: Vec<ModuleKind>,
+    inputs: Vec<Vec<usize>>,
+    // Each output along with the memory slot it writes to in the receiving module
+    outputs: Vec<Vec<(usize, usize)>>,
+    broadcaster: usize,
+    state: Snapshot,
+    subscriptions: Vec<Option<Subscription<'a>>>,
+}
+
+impl fmt::Debug for PulseNetwork<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PulseNetwork")
+            .field("names", &self.names)
+            .field("kinds", &self.kinds)
+            .field("inputs", &self.inputs)
+            .field("outputs", &self.outputs)
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+fn module(input: &str) -> IResult<&str, (ModuleKind, &str, Vec<&str>)> {
+    tuple((
+        alt((
+            value(ModuleKind::FlipFlop, tag("%")),
+            value(ModuleKind::Conjunction, tag("&")),
+            value(ModuleKind::Broadcaster, tag("")),
+        )),
+        terminated(alpha1, tag(" -> ")),
+        separated_list1(tag(", "), alpha1),
+    ))(input)
+}
+
+impl<'a> PulseNetwork<'a> {
+    fn id(&mut self, name: &'a str) -> usize {
+        *self.ids.entry(name).or_insert_with(|| {
+            self.names.push(name);
+            self.kinds.push(ModuleKind::Untyped);
+            self.inputs.push(Vec::new());
+            self.outputs.push(Vec::new());
+            self.names.len() - 1
+        })
+    }
+
+    pub fn module(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn name(&self, module: usize) -> &'a str {
+        self.names[module]
+    }
+
+    pub fn kind(&self, module: usize) -> ModuleKind {
+        self.kinds[module]
+    }
+
+    pub fn inputs(&self, module: usize) -> &[usize] {
+        &self.inputs[module]
+    }
+
+    pub fn outputs(&self, module: usize) -> impl Iterator<Item = usize> + '_ {
+        self.outputs[module].iter().map(|&(to, _)| to)
+    }
+
+    // Problems with the wiring that still let the network run, in module order.
+    pub fn warnings(&self) -> Vec<Warning<'a>> {
+        let mut reachable = vec![false; self.len()];
+        let mut stack = vec![self.broadcaster];
+        reachable[self.broadcaster] = true;
+        while let Some(module) = stack.pop() {
+            for next in self.outputs(module) {
+                if !reachable[next] {
+                    reachable[next] = true;
+                    stack.push(next);
+                }
+            }
+        }
+
+        let mut warnings = Vec::new();
+        for (module, &reachable) in reachable.iter().enumerate() {
+            let name = self.names[module];
+
+            if self.kinds[module] == ModuleKind::Untyped {
+                warnings.push(Warning::UndefinedTarget {
+                    name,
+                    referenced_by: self.inputs[module].iter().map(|&m| self.names[m]).collect(),
+                });
+            }
+            if !reachable {
+                warnings.push(Warning::Unreachable(name));
+            }
+            if self.kinds[module] == ModuleKind::Conjunction && self.inputs[module].is_empty() {
+                warnings.push(Warning::ConjunctionWithoutInputs(name));
+            }
+        }
+
+        warnings
+    }
+
+    pub fn presses(&self) -> usize {
+        self.state.presses
+    }
+
+    pub fn pulse_counts(&self) -> PulseCounts {
+        self.state.counts
+    }
+
+    pub fn is_on(&self, module: usize) -> bool {
+        self.state.on[module]
+    }
+
+    // The last pulse a conjunction received from each of its inputs, in input order.
+    pub fn memory(&self, module: usize) -> &[Pulse] {
+        &self.state.memories[module]
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        self.state.clone()
+    }
+
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        self.state.clone_from(snapshot);
+    }
+
+    // Calls `hook` with the press number and event whenever `to` receives `pulse`, as long
+    // as there is a module called `to`.
+    pub fn subscribe(
+        &mut self,
+        to: &str,
+        pulse: Pulse,
+        hook: impl FnMut(usize, &Event<'a>) + 'a,
+    ) -> Option<SubscriptionId> {
+        let to = self.module(to)?;
+
+        self.subscriptions.push(Some(Subscription {
+            to,
+            pulse,
+            hook: Box::new(hook),
+        }));
+        Some(SubscriptionId(self.subscriptions.len() - 1))
+    }
+
+    pub fn unsubscribe(&mut self, id: SubscriptionId) {
+        self.subscriptions[id.0] = None;
+    }
+
+    fn receive(&mut self, slot: usize, pulse: Pulse, to: usize) -> Option<Pulse> {
+        let state = &mut self.state;
+
+        match self.kinds[to] {
+            ModuleKind::Broadcaster => Some(pulse),
+            ModuleKind::FlipFlop => (pulse == Pulse::Low).then(|| {
+                state.on[to] = !state.on[to];
+                if state.on[to] {
+                    Pulse::High
+                } else {
+                    Pulse::Low
+                }
+            }),
+            ModuleKind::Conjunction => {
+                let memory = &mut state.memories[to][slot];
+                match (*memory, pulse) {
+                    (Pulse::Low, Pulse::High) => state.highs[to] += 1,
+                    (Pulse::High, Pulse::Low) => state.highs[to] -= 1,
+                    _ => (),
+                }
+                *memory = pulse;
+
+                if state.highs[to] == self.inputs[to].len() {
+                    Some(Pulse::Low)
+                } else {
+                    Some(Pulse::High)
+                }
+            }
+            ModuleKind::Untyped => None,
+        }
+    }
+
+    // Sends a low pulse to the broadcaster and returns every pulse sent until the
+    // network settles, in the order they were handled.
+    pub fn press_button(&mut self) -> Vec<Event<'a>> {
+        self.state.presses += 1;
+        let press = self.state.presses;
+
+        let mut trace = Vec::new();
+        let mut queue = VecDeque::from([(BUTTON, 0, Pulse::Low, self.broadcaster)]);
+
+        while let Some((from, slot, pulse, to)) = queue.pop_front() {
+            let event = Event {
+                from: self.names.get(from).copied().unwrap_or("button"),
+                pulse,
+                to: self.names[to],
+            };
+            self.state.counts.add(pulse);
+
+            for subscription in self.subscriptions.iter_mut().flatten() {
+                if subscription.to == to && subscription.pulse == pulse {
+                    (subscription.hook)(press, &event);
+                }
+            }
+            trace.push(event);
+
+            if let Some(pulse) = self.receive(slot, pulse, to) {
+                queue.extend(
+                    self.outputs[to]
+                        .iter()
+                        .map(|&(next, slot)| (to, slot, pulse, next)),
+                );
+            }
+        }
+
+        trace
+    }
+}
+
+impl<'a> From<&'a str> for PulseNetwork<'a> {
+    fn from(input: &'a str) -> Self {
+        let mut network = Self {
+            names: Vec::new(),
+            ids: HashMap::new(),
+            kinds: Vec::new(),
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            broadcaster: 0,
+            state: Snapshot {
+                on: Vec::new(),
+                memories: Vec::new(),
+                highs: Vec::new(),
+                counts: PulseCounts::default(),
+                presses: 0,
+            },
+            subscriptions: Vec::new(),
+        };
+
+        for line in input.lines().filter(|line| !line.trim().is_empty()) {
+            let (_, (kind, name, targets)) =
+                all_consuming(module)(line.trim_end()).expect("Must match the parser");
+
+            let from = network.id(name);
+            network.kinds[from] = kind;
+            for target in targets {
+                let to = network.id(target);
+                network.outputs[from].push((to, network.inputs[to].len()));
+                network.inputs[to].push(from);
+            }
+        }
+
+        network.broadcaster = network
+            .module("broadcaster")
+            .expect("Must have a broadcaster");
+        network.state.on = vec![false; network.len()];
+        network.state.memories = network
+            .inputs
+            .iter()
+            .map(|inputs| vec![Pulse::Low; inputs.len()])
+            .collect();
+        network.state.highs = vec![0; network.len()];
+
+        network
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[test]
+    fn test_press_button() {
+        let mut network = PulseNetwork::from(include_str!("../data/example2.txt"));
+
+        let trace = |network: &mut PulseNetwork| {
+            network
+                .press_button()
+                .iter()
+                .map(|event| event.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            trace(&mut network),
+            [
+                "button -low-> broadcaster",
+                "broadcaster -low-> a",
+                "a -high-> inv",
+                "a -high-> con",
+                "inv -low-> b",
+                "con -high-> output",
+                "b -high-> con",
+                "con -low-> output",
+            ]
+        );
+        assert_eq!(
+            trace(&mut network),
+            [
+                "button -low-> broadcaster",
+                "broadcaster -low-> a",
+                "a -low-> inv",
+                "a -low-> con",
+                "inv -high-> b",
+                "con -high-> output",
+            ]
+        );
+        assert_eq!(network.presses(), 2);
+        assert_eq!(network.pulse_counts(), PulseCounts { low: 8, high: 6 });
+    }
+
+    #[test]
+    fn test_subscribe() {
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let mut network = PulseNetwork::from(include_str!("../data/example2.txt"));
+
+        let log = Rc::clone(&received);
+        let id = network
+            .subscribe("output", Pulse::Low, move |press, event| {
+                log.borrow_mut().push((press, event.from.to_string()))
+            })
+            .unwrap();
+        (0..4).for_each(|_| {
+            network.press_button();
+        });
+        network.unsubscribe(id);
+        network.press_button();
+
+        assert_eq!(
+            *received.borrow(),
+            vec![(1, "con".to_string()), (3, "con".to_string())]
+        );
+        assert!(network
+            .subscribe("missing", Pulse::Low, |_, _| ())
+            .is_none());
+    }
+
+    #[test]
+    fn test_warnings() {
+        let network = PulseNetwork::from(include_str!("../data/example2.txt"));
+        assert_eq!(
+            network.warnings(),
+            vec![Warning::UndefinedTarget {
+                name: "output",
+                referenced_by: vec!["con"],
+            }]
+        );
+
+        let network = PulseNetwork::from("broadcaster -> a\n%a -> rx\n&lonely -> a, b\n");
+        assert_eq!(
+            network
+                .warnings()
+                .iter()
+                .map(|warning| warning.to_string())
+                .collect::<Vec<_>>(),
+            [
+                "module 'rx' is sent pulses by a but never defined",
+                "module 'lonely' is unreachable",
+                "conjunction 'lonely' has no inputs",
+                "module 'b' is sent pulses by lonely but never defined",
+                "module 'b' is unreachable",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let mut network = PulseNetwork::from(include_str!("../data/example2.txt"));
+        network.press_button();
+        let snapshot = network.snapshot();
+
+        let first = network.press_button();
+        network.press_button();
+        assert_ne!(network.snapshot(), snapshot);
+
+        network.restore(&snapshot);
+        assert_eq!(network.presses(), 1);
+        assert_eq!(network.press_button(), first);
+    }
+}
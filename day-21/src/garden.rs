@@ -0,0 +1,286 @@
+use std::collections::VecDeque;
+
+use grid::Grid;
+use itertools::Itertools;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Garden {
+    grid: Grid<char>,
+    start: (usize, usize),
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+impl Garden {
+    pub fn rows(&self) -> usize {
+        self.grid.rows()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.grid.cols()
+    }
+
+    pub fn start(&self) -> (usize, usize) {
+        self.start
+    }
+
+    // Whether a plot can be stepped on, with the garden repeating infinitely in every
+    // direction.
+    pub fn is_plot(&self, row: isize, col: isize) -> bool {
+        let row = row.rem_euclid(self.rows() as isize) as usize;
+        let col = col.rem_euclid(self.cols() as isize) as usize;
+        self.grid[(row, col)] != '#'
+    }
+
+    // Walking distances from the start over a `tiles` by `tiles` block of gardens with the
+    // start's garden in the middle. A single tile is the garden on its own.
+    pub fn distances(&self, tiles: usize) -> DistanceField<'_> {
+        assert!(tiles % 2 == 1, "Must have a middle tile");
+
+        let offset = (tiles / 2 * self.rows(), tiles / 2 * self.cols());
+        let start = (self.start.0 + offset.0, self.start.1 + offset.1);
+        let mut distances = Grid::init(tiles * self.rows(), tiles * self.cols(), None);
+        distances[start] = Some(0);
+        let mut queue = VecDeque::from([(start, 0)]);
+
+        while let Some(((row, col), distance)) = queue.pop_front() {
+            for next in [
+                (row.wrapping_sub(1), col),
+                (row + 1, col),
+                (row, col.wrapping_sub(1)),
+                (row, col + 1),
+            ] {
+                let Some(cell) = distances.get_mut(next.0, next.1) else {
+                    continue;
+                };
+                if cell.is_none() && self.is_plot(next.0 as isize, next.1 as isize) {
+                    *cell = Some(distance + 1);
+                    queue.push_back((next, distance + 1));
+                }
+            }
+        }
+
+        DistanceField {
+            garden: self,
+            offset,
+            distances,
+        }
+    }
+
+    // The number of plots reachable in exactly `n` steps for every `n` up to `max_steps`,
+    // over enough tiles that none of the walks can reach the edge of them.
+    pub fn reachable_counts(&self, max_steps: usize) -> Vec<usize> {
+        let tiles = 2 * (max_steps / self.rows().min(self.cols()) + 1) + 1;
+        let field = self.distances(tiles);
+        assert!(field.horizon() >= max_steps, "Must cover every step");
+
+        field.reachable_counts(max_steps)
+    }
+
+    // Once the steps reach far enough that every tile in the middle of the diamond is
+    // filled in, each further period of the garden adds a ring of tiles whose size grows
+    // linearly, so counts sampled a period apart follow a quadratic. Three samples fit it
+    // and a fourth checks it, pushing the samples further out until they agree. Step counts
+    // too small to sample before are counted directly.
+    pub fn reachable(&self, steps: usize) -> usize {
+        let period = self.rows() / gcd(self.rows(), self.cols()) * self.cols();
+        let remainder = steps % period;
+        let mut counts = Vec::new();
+
+        for first in (remainder..).step_by(period) {
+            let last = first + 3 * period;
+            if steps <= last {
+                break;
+            }
+            if counts.len() <= last {
+                counts = self.reachable_counts((2 * counts.len()).clamp(last, steps));
+            }
+
+            let [a, b, c, d] = [0, 1, 2, 3].map(|i| counts[first + i * period] as i64);
+            if d - 3 * c + 3 * b - a == 0 {
+                let n = ((steps - first) / period) as i64;
+                return (a + n * (b - a) + n * (n - 1) / 2 * (c - 2 * b + a)) as usize;
+            }
+        }
+
+        if counts.len() <= steps {
+            counts = self.reachable_counts(steps);
+        }
+        counts[steps]
+    }
+}
+
+// How many steps it takes to reach each plot in a block of tiled gardens, if it can be
+// reached at all. Rocks and walled-off plots have no distance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DistanceField<'a> {
+    garden: &'a Garden,
+    // Where the start's garden begins within the field
+    offset: (usize, usize),
+    distances: Grid<Option<usize>>,
+}
+
+impl DistanceField<'_> {
+    pub fn distances(&self) -> &Grid<Option<usize>> {
+        &self.distances
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<usize> {
+        self.distances.get(row, col).copied().flatten()
+    }
+
+    // Plots can be stood on after exactly `n` steps if they are no further than `n` and
+    // the same number of steps as `n` apart, stepping back and forth to use up the rest.
+    pub fn is_reachable_in(&self, row: usize, col: usize, n: usize) -> bool {
+        self.get(row, col)
+            .is_some_and(|distance| distance <= n && distance % 2 == n % 2)
+    }
+
+    pub fn reachable_in(&self, n: usize) -> usize {
+        self.distances
+            .iter()
+            .flatten()
+            .filter(|&&distance| distance <= n && distance % 2 == n % 2)
+            .count()
+    }
+
+    // The number of plots reachable in exactly `n` steps for every `n` up to `max_steps`,
+    // each building on the count two steps before.
+    pub fn reachable_counts(&self, max_steps: usize) -> Vec<usize> {
+        let mut counts = vec![0; max_steps + 1];
+        for &distance in self.distances.iter().flatten() {
+            if distance <= max_steps {
+                counts[distance] += 1;
+            }
+        }
+        for n in 2..=max_steps {
+            counts[n] += counts[n - 2];
+        }
+        counts
+    }
+
+    // The fewest steps to reach the edge of the field. Walks no longer than this never
+    // leave it, so they count the same as they would in an endless garden.
+    pub fn horizon(&self) -> usize {
+        let (rows, cols) = self.distances.size();
+        self.distances
+            .indexed_iter()
+            .filter(|&((row, col), _)| row == 0 || col == 0 || row == rows - 1 || col == cols - 1)
+            .filter_map(|(_, &distance)| distance)
+            .min()
+            .unwrap_or(usize::MAX)
+    }
+
+    // The field as it would be drawn in the puzzle, marking plots reachable in exactly `n`
+    // steps with an `O`.
+    pub fn render(&self, n: usize) -> String {
+        let (rows, cols) = (self.garden.rows(), self.garden.cols());
+        let start = (
+            self.garden.start.0 + self.offset.0,
+            self.garden.start.1 + self.offset.1,
+        );
+
+        (0..self.distances.rows())
+            .map(|row| {
+                (0..self.distances.cols())
+                    .map(|col| match self.garden.grid[(row % rows, col % cols)] {
+                        _ if self.is_reachable_in(row, col, n) => 'O',
+                        'S' if (row, col) != start => '.',
+                        cell => cell,
+                    })
+                    .collect::<String>()
+            })
+            .join("\n")
+    }
+}
+
+impl From<&str> for Garden {
+    fn from(input: &str) -> Self {
+        let grid = Grid::from(input.lines().map(|l| l.chars().collect()).collect_vec());
+        let (start, _) = grid
+            .indexed_iter()
+            .find(|(_, &cell)| cell == 'S')
+            .expect("Must have a starting point");
+
+        Garden { grid, start }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reachable_counts() {
+        let garden = Garden::from(include_str!("../data/example.txt"));
+        assert_eq!(garden.start(), (5, 5));
+        assert_eq!(garden.reachable_counts(10)[6], 16);
+        assert_eq!(garden.reachable_counts(10)[10], 50);
+    }
+
+    #[test]
+    fn test_distances() {
+        let garden = Garden::from(include_str!("../data/example.txt"));
+        let field = garden.distances(1);
+        assert_eq!(field.get(5, 5), Some(0));
+        assert_eq!(field.get(4, 5), Some(1));
+        assert_eq!(field.get(5, 6), None);
+        assert_eq!(field.reachable_in(6), 16);
+        assert_eq!(field.horizon(), 6);
+
+        assert_eq!(
+            field.render(6),
+            [
+                "...........",
+                ".....###.#.",
+                ".###.##.O#.",
+                ".O#O#O.O#..",
+                "O.O.#.#.O..",
+                ".##O.O####.",
+                ".##.O#O..#.",
+                ".O.O.O.##..",
+                ".##.#.####.",
+                ".##O.##.##.",
+                "...........",
+            ]
+            .join("\n")
+        );
+        assert!(field.render(1).contains(".##.OS####."));
+
+        let tiled = garden.distances(3);
+        assert_eq!(tiled.get(16, 16), Some(0));
+        assert_eq!(tiled.distances().size(), (33, 33));
+        assert_eq!(tiled.reachable_counts(10), garden.reachable_counts(10));
+        assert_eq!(tiled.reachable_in(10), 50);
+    }
+
+    #[test]
+    fn test_reachable() {
+        let garden = Garden::from(include_str!("../data/example.txt"));
+        for (steps, expected) in [
+            (6, 16),
+            (10, 50),
+            (50, 1594),
+            (100, 6536),
+            (500, 167004),
+            (1000, 668697),
+            (5000, 16733044),
+        ] {
+            assert_eq!(garden.reachable(steps), expected);
+        }
+    }
+
+    #[test]
+    fn test_reachable_off_edge() {
+        let garden = Garden::from(include_str!("../data/input.txt"));
+        let counts = garden.reachable_counts(600);
+        assert_eq!(garden.reachable(600), counts[600]);
+        assert_eq!(garden.reachable(555), counts[555]);
+    }
+}
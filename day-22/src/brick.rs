@@ -0,0 +1,151 @@
+use std::cmp::Ordering;
+
+pub type ID = usize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
+    pub z: isize,
+}
+
+impl Point {
+    pub fn distance_to(&self, other: &Self) -> (isize, isize, isize) {
+        (
+            (self.x - other.x).abs(),
+            (self.y - other.y).abs(),
+            (self.z - other.z).abs(),
+        )
+    }
+}
+
+impl Ord for Point {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.z
+            .cmp(&other.z)
+            .then_with(|| self.x.cmp(&other.x))
+            .then_with(|| self.y.cmp(&other.y))
+    }
+}
+
+impl PartialOrd for Point {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<(isize, isize, isize)> for Point {
+    fn from((x, y, z): (isize, isize, isize)) -> Self {
+        Self { x, y, z }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Brick {
+    id: ID,
+    start: Point,
+    end: Point,
+}
+
+impl Brick {
+    pub fn id(&self) -> ID {
+        self.id
+    }
+
+    pub fn start(&self) -> Point {
+        self.start
+    }
+
+    pub fn end(&self) -> Point {
+        self.end
+    }
+
+    pub fn height(&self) -> isize {
+        self.end.z - self.start.z + 1
+    }
+
+    // The (x, y) cells the brick covers when seen from above
+    pub fn footprint(&self) -> impl Iterator<Item = (isize, isize)> {
+        let (start, end) = (self.start, self.end);
+        (start.x..=end.x).flat_map(move |x| (start.y..=end.y).map(move |y| (x, y)))
+    }
+
+    // The same brick moved so its lowest cube sits at `z`
+    pub fn lowered_to(&self, z: isize) -> Self {
+        let drop = self.start.z - z;
+        Self {
+            start: Point::from((self.start.x, self.start.y, z)),
+            end: Point::from((self.end.x, self.end.y, self.end.z - drop)),
+            ..*self
+        }
+    }
+}
+
+impl Ord for Brick {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.start
+            .cmp(&other.start)
+            .then_with(|| self.end.cmp(&other.end))
+    }
+}
+
+impl PartialOrd for Brick {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<((Point, Point), usize)> for Brick {
+    fn from(((start, end), id): ((Point, Point), usize)) -> Self {
+        assert!(
+            matches!(start.distance_to(&end), (0, 0, _) | (0, _, 0) | (_, 0, 0)),
+            "A brick must have at least two dimensions that are 1x1"
+        );
+
+        Self {
+            id,
+            start: start.min(end),
+            end: end.max(start),
+        }
+    }
+}
+
+pub mod parser {
+    use super::*;
+    use nom::{
+        bytes::complete::tag,
+        character::complete::{self, line_ending},
+        combinator::map,
+        multi::separated_list1,
+        sequence::{separated_pair, terminated, tuple},
+        IResult,
+    };
+
+    fn digit(input: &str) -> IResult<&str, isize> {
+        map(complete::i64, |num| num as isize)(input)
+    }
+
+    fn point(input: &str) -> IResult<&str, Point> {
+        map(
+            tuple((
+                terminated(digit, tag(",")),
+                terminated(digit, tag(",")),
+                digit,
+            )),
+            Point::from,
+        )(input)
+    }
+
+    pub fn parse(input: &str) -> IResult<&str, Vec<Brick>> {
+        let mut current_id = 0_usize;
+        let mut parser = separated_list1(
+            line_ending,
+            map(separated_pair(point, tag("~"), point), |points| {
+                current_id += 1;
+                Brick::from((points, current_id))
+            }),
+        );
+
+        parser(input)
+    }
+}
@@ -0,0 +1,433 @@
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+};
+
+use crate::brick::{parser, Brick, Point, ID};
+
+// Which axis runs left to right when looking at the stack from the side
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Axis {
+    X,
+    Y,
+}
+
+// The bricks once they have all fallen as far as they can, along with which bricks rest
+// on which. Bricks are kept from the ground up, so every brick comes after the bricks
+// holding it up, but are always referred to by their ID from the snapshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stack {
+    bricks: Vec<Brick>,
+    indices: HashMap<ID, usize>,
+    supports: Vec<Vec<usize>>,
+    supported_by: Vec<Vec<usize>>,
+}
+
+// Walks the deeper of the two up the dominator tree until they meet.
+fn common_dominator(
+    dominators: &[Option<usize>],
+    depths: &[usize],
+    mut a: Option<usize>,
+    mut b: Option<usize>,
+) -> Option<usize> {
+    let depth = |brick: Option<usize>| brick.map_or(0, |brick| depths[brick]);
+
+    while a != b {
+        if depth(a) >= depth(b) {
+            a = a.and_then(|brick| dominators[brick]);
+        } else {
+            b = b.and_then(|brick| dominators[brick]);
+        }
+    }
+
+    a
+}
+
+impl Stack {
+    // Drops the bricks lowest first onto a height map of the tallest brick in each column,
+    // so each brick only looks at the columns under it.
+    pub fn settle(mut bricks: Vec<Brick>) -> Self {
+        bricks.sort();
+
+        let mut heights: HashMap<(isize, isize), (isize, usize)> = HashMap::new();
+        let mut settled = Vec::with_capacity(bricks.len());
+        let mut supports = vec![Vec::new(); bricks.len()];
+        let mut supported_by = Vec::with_capacity(bricks.len());
+
+        for (index, brick) in bricks.iter().enumerate() {
+            let below: Vec<_> = brick
+                .footprint()
+                .filter_map(|cell| heights.get(&cell).copied())
+                .collect();
+            let top = below.iter().map(|&(z, _)| z).max().unwrap_or(0);
+
+            let mut resting_on: Vec<_> = below
+                .iter()
+                .filter(|&&(z, _)| z == top)
+                .map(|&(_, below)| below)
+                .collect();
+            resting_on.sort_unstable();
+            resting_on.dedup();
+            for &below in &resting_on {
+                supports[below].push(index);
+            }
+            supported_by.push(resting_on);
+
+            let brick = brick.lowered_to(top + 1);
+            for cell in brick.footprint() {
+                heights.insert(cell, (brick.end().z, index));
+            }
+            settled.push(brick);
+        }
+
+        Stack {
+            indices: settled
+                .iter()
+                .enumerate()
+                .map(|(index, brick)| (brick.id(), index))
+                .collect(),
+            bricks: settled,
+            supports,
+            supported_by,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.bricks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bricks.is_empty()
+    }
+
+    // The bricks from the ground up
+    pub fn bricks(&self) -> &[Brick] {
+        &self.bricks
+    }
+
+    pub fn brick(&self, id: ID) -> Option<&Brick> {
+        self.indices.get(&id).map(|&index| &self.bricks[index])
+    }
+
+    fn ids(&self, indices: &[usize]) -> Vec<ID> {
+        indices
+            .iter()
+            .map(|&index| self.bricks[index].id())
+            .collect()
+    }
+
+    // The bricks resting directly on top of `brick`
+    pub fn supports(&self, brick: ID) -> Option<Vec<ID>> {
+        let index = *self.indices.get(&brick)?;
+        Some(self.ids(&self.supports[index]))
+    }
+
+    // The bricks `brick` rests directly on, which is none for bricks on the ground
+    pub fn supported_by(&self, brick: ID) -> Option<Vec<ID>> {
+        let index = *self.indices.get(&brick)?;
+        Some(self.ids(&self.supported_by[index]))
+    }
+
+    // Bricks that can be disintegrated without any other brick falling
+    pub fn safe_to_disintegrate(&self) -> impl Iterator<Item = &Brick> {
+        self.bricks.iter().enumerate().filter_map(|(index, brick)| {
+            self.supports[index]
+                .iter()
+                .all(|&above| self.supported_by[above].len() > 1)
+                .then_some(brick)
+        })
+    }
+
+    // The immediate dominator of each brick in the support graph, with `None` standing for
+    // the ground. A brick's dominators are the bricks that every path down to the ground
+    // passes through, so removing a brick brings down exactly the bricks it dominates.
+    pub fn dominators(&self) -> HashMap<ID, Option<ID>> {
+        self.dominator_indices()
+            .into_iter()
+            .enumerate()
+            .map(|(index, dominator)| {
+                (
+                    self.bricks[index].id(),
+                    dominator.map(|dominator| self.bricks[dominator].id()),
+                )
+            })
+            .collect()
+    }
+
+    // The same, by position from the ground up
+    fn dominator_indices(&self) -> Vec<Option<usize>> {
+        let mut dominators = vec![None; self.len()];
+        let mut depths = vec![0; self.len()];
+
+        for brick in 0..self.len() {
+            let mut below = self.supported_by[brick].iter().map(|&below| Some(below));
+            let first = below.next().flatten();
+            dominators[brick] =
+                below.fold(first, |a, b| common_dominator(&dominators, &depths, a, b));
+            depths[brick] = dominators[brick].map_or(1, |dominator| depths[dominator] + 1);
+        }
+
+        dominators
+    }
+
+    // How many other bricks fall when each brick is disintegrated, counted as the size of
+    // its subtree in the dominator tree.
+    pub fn chain_reactions(&self) -> HashMap<ID, usize> {
+        let dominators = self.dominator_indices();
+        let mut sizes = vec![1; self.len()];
+
+        for brick in (0..self.len()).rev() {
+            if let Some(dominator) = dominators[brick] {
+                sizes[dominator] += sizes[brick];
+            }
+        }
+
+        sizes
+            .into_iter()
+            .enumerate()
+            .map(|(index, size)| (self.bricks[index].id(), size - 1))
+            .collect()
+    }
+
+    // The bricks that fall when every brick in `removed` is taken away at once
+    pub fn would_fall(&self, removed: &[ID]) -> Vec<ID> {
+        let removed: HashSet<_> = removed.iter().collect();
+        let mut gone = vec![false; self.len()];
+        let mut falling = Vec::new();
+
+        for (index, brick) in self.bricks.iter().enumerate() {
+            let below = &self.supported_by[index];
+            if removed.contains(&brick.id()) {
+                gone[index] = true;
+            } else if !below.is_empty() && below.iter().all(|&below| gone[below]) {
+                gone[index] = true;
+                falling.push(brick.id());
+            }
+        }
+
+        falling
+    }
+
+    // An order to take the whole stack apart one brick at a time without anything ever
+    // falling, taking the lowest brick that can go at each step.
+    pub fn removal_order(&self) -> Vec<ID> {
+        let mut remaining = vec![true; self.len()];
+        let mut order = Vec::with_capacity(self.len());
+
+        while order.len() < self.len() {
+            let next = (0..self.len())
+                .find(|&index| {
+                    remaining[index]
+                        && self.supports[index].iter().all(|&above| {
+                            !remaining[above]
+                                || self.supported_by[above]
+                                    .iter()
+                                    .any(|&below| below != index && remaining[below])
+                        })
+                })
+                .expect("Must be able to remove the top brick");
+
+            remaining[next] = false;
+            order.push(self.bricks[next].id());
+        }
+
+        order
+    }
+
+    // The most bricks stacked one on top of the other from the ground up, lowest first
+    pub fn tallest_chain(&self) -> Vec<ID> {
+        let mut lengths = vec![0; self.len()];
+        let mut previous = vec![None; self.len()];
+
+        for index in 0..self.len() {
+            let below = self.supported_by[index]
+                .iter()
+                .copied()
+                .max_by_key(|&below| (lengths[below], Reverse(below)));
+            lengths[index] = below.map_or(0, |below| lengths[below]) + 1;
+            previous[index] = below;
+        }
+
+        let Some(top) = (0..self.len()).max_by_key(|&index| (lengths[index], Reverse(index)))
+        else {
+            return Vec::new();
+        };
+
+        let mut chain: Vec<_> = std::iter::successors(Some(top), |&index| previous[index])
+            .map(|index| self.bricks[index].id())
+            .collect();
+        chain.reverse();
+        chain
+    }
+
+    // The stack seen from the side like in the puzzle, with `axis` going left to right and
+    // each brick lettered by its id. `?` marks where more than one brick lines up.
+    pub fn project(&self, axis: Axis) -> String {
+        let across = |point: Point| match axis {
+            Axis::X => point.x,
+            Axis::Y => point.y,
+        };
+        let width = self
+            .bricks
+            .iter()
+            .map(|b| across(b.end()) + 1)
+            .max()
+            .unwrap_or(0);
+        let height = self.bricks.iter().map(|b| b.end().z).max().unwrap_or(0);
+
+        let mut cells: HashMap<(isize, isize), Option<ID>> = HashMap::new();
+        for brick in &self.bricks {
+            for position in across(brick.start())..=across(brick.end()) {
+                for z in brick.start().z..=brick.end().z {
+                    cells
+                        .entry((position, z))
+                        .and_modify(|cell| *cell = None)
+                        .or_insert(Some(brick.id()));
+                }
+            }
+        }
+
+        let label = match axis {
+            Axis::X => 'x',
+            Axis::Y => 'y',
+        };
+        let mut lines = vec![
+            format!("{}{label}", " ".repeat(width as usize / 2)),
+            (0..width)
+                .map(|position| (position % 10).to_string())
+                .collect(),
+        ];
+        for z in (1..=height).rev() {
+            let row: String = (0..width)
+                .map(|position| match cells.get(&(position, z)) {
+                    None => '.',
+                    Some(None) => '?',
+                    Some(&Some(id)) => char::from(b'A' + ((id - 1) % 26) as u8),
+                })
+                .collect();
+            let axis = if z == (height + 1) / 2 { " z" } else { "" };
+            lines.push(format!("{row} {z}{axis}"));
+        }
+        lines.push(format!("{} 0", "-".repeat(width as usize)));
+
+        lines.join("\n")
+    }
+}
+
+impl From<&str> for Stack {
+    fn from(input: &str) -> Self {
+        let (_, bricks) = parser::parse(input).expect("Must parse bricks");
+        Stack::settle(bricks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settle() {
+        let stack = Stack::from(include_str!("../data/example.txt"));
+
+        let ids: Vec<_> = stack.bricks().iter().map(|brick| brick.id()).collect();
+        assert_eq!(ids, [1, 2, 3, 4, 5, 6, 7]);
+        let bottoms: Vec<_> = stack.bricks().iter().map(|b| b.start().z).collect();
+        assert_eq!(bottoms, [1, 2, 2, 3, 3, 4, 5]);
+
+        assert_eq!(stack.supports(1), Some(vec![2, 3]));
+        assert_eq!(stack.supported_by(4), Some(vec![2, 3]));
+        assert_eq!(stack.supported_by(6), Some(vec![4, 5]));
+        assert_eq!(stack.supported_by(1), Some(vec![]));
+        assert_eq!(stack.supports(8), None);
+        assert_eq!(stack.brick(7).map(|b| b.start().z), Some(5));
+    }
+
+    #[test]
+    fn test_ids_out_of_order() {
+        // Brick 1 starts above brick 2, so it settles second
+        let stack = Stack::from("1,0,2~1,0,2\n1,0,1~1,0,1\n");
+
+        let ids: Vec<_> = stack.bricks().iter().map(|brick| brick.id()).collect();
+        assert_eq!(ids, [2, 1]);
+        assert_eq!(stack.supports(2), Some(vec![1]));
+        assert_eq!(stack.supported_by(1), Some(vec![2]));
+        assert_eq!(stack.dominators(), HashMap::from([(2, None), (1, Some(2))]));
+        assert_eq!(stack.chain_reactions(), HashMap::from([(2, 1), (1, 0)]));
+        assert_eq!(stack.would_fall(&[2]), [1]);
+    }
+
+    #[test]
+    fn test_dominators() {
+        let stack = Stack::from(include_str!("../data/example.txt"));
+
+        assert_eq!(
+            stack.dominators(),
+            HashMap::from([
+                (1, None),
+                (2, Some(1)),
+                (3, Some(1)),
+                (4, Some(1)),
+                (5, Some(1)),
+                (6, Some(1)),
+                (7, Some(6)),
+            ])
+        );
+        assert_eq!(
+            stack.chain_reactions(),
+            HashMap::from([(1, 6), (2, 0), (3, 0), (4, 0), (5, 0), (6, 1), (7, 0)])
+        );
+
+        let ids: Vec<_> = stack.safe_to_disintegrate().map(|b| b.id()).collect();
+        assert_eq!(ids, [2, 3, 4, 5, 7]);
+    }
+    #[test]
+    fn test_queries() {
+        let stack = Stack::from(include_str!("../data/example.txt"));
+
+        assert_eq!(stack.would_fall(&[1]), [2, 3, 4, 5, 6, 7]);
+        assert!(stack.would_fall(&[2]).is_empty());
+        assert_eq!(stack.would_fall(&[2, 3]), [4, 5, 6, 7]);
+        assert_eq!(stack.would_fall(&[4, 5]), [6, 7]);
+
+        assert_eq!(stack.removal_order(), [2, 4, 7, 6, 5, 3, 1]);
+        assert_eq!(stack.tallest_chain(), [1, 2, 4, 6, 7]);
+    }
+
+    #[test]
+    fn test_removal_order() {
+        let stack = Stack::from(include_str!("../data/input.txt"));
+        let order = stack.removal_order();
+        assert_eq!(order.len(), stack.len());
+
+        for removed in 1..=order.len() {
+            assert!(stack.would_fall(&order[..removed]).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_project() {
+        let stack = Stack::from(include_str!("../data/example.txt"));
+
+        assert_eq!(
+            stack.project(Axis::X),
+            [" x", "012", ".G. 6", ".G. 5", "FFF 4", "D.E 3 z", "??? 2", ".A. 1", "--- 0",]
+                .join("\n")
+        );
+        assert_eq!(
+            stack.project(Axis::Y),
+            [" y", "012", ".G. 6", ".G. 5", ".F. 4", "??? 3 z", "B.C 2", "AAA 1", "--- 0",]
+                .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_chain_reactions() {
+        let stack = Stack::from(include_str!("../data/input.txt"));
+        let chain_reactions = stack.chain_reactions();
+        assert_eq!(chain_reactions.len(), stack.len());
+
+        for (&brick, &falls) in &chain_reactions {
+            assert_eq!(falls, stack.would_fall(&[brick]).len());
+        }
+    }
+}
@@ -0,0 +1,485 @@
+use grid::Grid;
+use helpers::search::{bfs, SearchSpace};
+use itertools::Itertools;
+use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
+
+type Position = (usize, usize);
+
+// Whether the slopes `^v<>` force the next step downhill or are walked like any other path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SlopeMode {
+    Directed,
+    Undirected,
+}
+
+// The longest hike found, along with the junctions it passes through in order
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Hike {
+    pub length: usize,
+    pub junctions: Vec<Position>,
+}
+
+// The trails squashed down to the places where they branch. Every corridor between two
+// junctions becomes a single edge weighted by its length, which leaves only a few dozen
+// junctions for the search to choose between.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JunctionGraph {
+    junctions: Vec<Position>,
+    edges: Vec<Vec<(usize, usize)>>,
+    start: usize,
+    end: usize,
+}
+
+fn open_neighbors(grid: &Grid<char>, (row, col): Position) -> impl Iterator<Item = Position> + '_ {
+    [
+        (row.wrapping_sub(1), col),
+        (row + 1, col),
+        (row, col.wrapping_sub(1)),
+        (row, col + 1),
+    ]
+    .into_iter()
+    .filter(|&(row, col)| grid.get(row, col).is_some_and(|&cell| cell != '#'))
+}
+
+// Slopes can only be stepped off in the direction they point.
+fn is_downhill(grid: &Grid<char>, from: Position, to: Position) -> bool {
+    let downhill = match grid[from] {
+        '^' => (from.0.wrapping_sub(1), from.1),
+        'v' => (from.0 + 1, from.1),
+        '<' => (from.0, from.1.wrapping_sub(1)),
+        '>' => (from.0, from.1 + 1),
+        _ => return true,
+    };
+    downhill == to
+}
+
+// The open tiles of the map, leaving out the junction a corridor is being walked from.
+struct Trails<'a> {
+    grid: &'a Grid<char>,
+    mode: SlopeMode,
+    from: Position,
+}
+
+impl SearchSpace for Trails<'_> {
+    type State = Position;
+
+    fn state_count(&self) -> usize {
+        self.grid.rows() * self.grid.cols()
+    }
+
+    fn index(&self, (row, col): Self::State) -> usize {
+        row * self.grid.cols() + col
+    }
+
+    fn state(&self, index: usize) -> Self::State {
+        (index / self.grid.cols(), index % self.grid.cols())
+    }
+
+    fn successors(&self, state: Self::State, successors: &mut Vec<(Self::State, usize)>) {
+        successors.extend(
+            open_neighbors(self.grid, state)
+                .filter(|&next| next != self.from)
+                .filter(|&next| {
+                    self.mode == SlopeMode::Undirected || is_downhill(self.grid, state, next)
+                })
+                .map(|next| (next, 1)),
+        );
+    }
+}
+
+impl JunctionGraph {
+    pub fn junctions(&self) -> &[Position] {
+        &self.junctions
+    }
+
+    // The junctions reachable from `junction` and how many steps away they are
+    pub fn edges(&self, junction: usize) -> &[(usize, usize)] {
+        &self.edges[junction]
+    }
+
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    // Follows the corridor leaving `from` through `first` until it reaches another junction,
+    // giving up if it goes back up a slope.
+    fn walk(
+        &self,
+        grid: &Grid<char>,
+        mode: SlopeMode,
+        from: Position,
+        first: Position,
+    ) -> Option<(usize, usize)> {
+        if mode == SlopeMode::Directed && !is_downhill(grid, from, first) {
+            return None;
+        }
+
+        let trails = Trails { grid, mode, from };
+        let path = bfs(&trails, [first], |position| {
+            self.junctions.contains(&position)
+        })
+        .path?;
+        let last = path.states.last().expect("Must end on a junction");
+        let junction = self
+            .junctions
+            .iter()
+            .position(|j| j == last)
+            .expect("Must be a junction");
+
+        Some((junction, path.cost + 1))
+    }
+
+    // The junctions in an order where every edge goes forwards, if there are no cycles.
+    pub fn topological_order(&self) -> Option<Vec<usize>> {
+        let mut incoming = vec![0; self.junctions.len()];
+        for &(to, _) in self.edges.iter().flatten() {
+            incoming[to] += 1;
+        }
+
+        let mut order: Vec<_> = (0..self.junctions.len())
+            .filter(|&junction| incoming[junction] == 0)
+            .collect();
+        let mut next = 0;
+        while let Some(&junction) = order.get(next) {
+            for &(to, _) in &self.edges[junction] {
+                incoming[to] -= 1;
+                if incoming[to] == 0 {
+                    order.push(to);
+                }
+            }
+            next += 1;
+        }
+
+        (order.len() == self.junctions.len()).then_some(order)
+    }
+
+    // Without cycles no hike can come back to a junction, so the longest hike to each
+    // junction only needs to be worked out once, going through them in topological order.
+    fn dag_longest_hike(&self, order: &[usize]) -> Option<Hike> {
+        let mut longest: Vec<Option<(usize, usize)>> = vec![None; self.junctions.len()];
+        let mut reached = vec![false; self.junctions.len()];
+        reached[self.start] = true;
+
+        for &junction in order {
+            if !reached[junction] {
+                continue;
+            }
+            let length = longest[junction].map_or(0, |(length, _)| length);
+
+            for &(to, step) in &self.edges[junction] {
+                if longest[to].is_none_or(|(best, _)| length + step > best) {
+                    longest[to] = Some((length + step, junction));
+                }
+                reached[to] = true;
+            }
+        }
+
+        let (length, _) = longest[self.end]?;
+        let mut path: Vec<_> = std::iter::successors(Some(self.end), |&junction| {
+            longest[junction].map(|(_, previous)| previous)
+        })
+        .collect();
+        path.reverse();
+        Some(self.hike((length, path)))
+    }
+
+    // The only way into the end is usually through one junction, so reaching that junction
+    // finishes the hike, since going anywhere else would cut it off from the end. Gives
+    // the junction hikes finish at and how many steps are left from there.
+    fn target(&self) -> (usize, usize) {
+        let into_end: Vec<_> = (0..self.junctions.len())
+            .filter_map(|junction| {
+                self.edges[junction]
+                    .iter()
+                    .find(|&&(to, _)| to == self.end)
+                    .map(|&(_, length)| (junction, length))
+            })
+            .collect();
+
+        match into_end[..] {
+            [(junction, length)] => (junction, length),
+            _ => (self.end, 0),
+        }
+    }
+
+    // Searches every hike carrying on from `prefix` that never steps on the same junction
+    // twice.
+    fn search(&self, prefix: &[usize], length: usize) -> Option<(usize, Vec<usize>)> {
+        let (target, remaining) = self.target();
+        let mut search = Search {
+            graph: self,
+            target,
+            remaining,
+            path: prefix.to_vec(),
+            best: None,
+        };
+        let visited = prefix.iter().fold(0, |visited, &j| visited | 1 << j);
+        search.visit(visited, length);
+        search.best
+    }
+
+    // The different ways a hike can start, grown a junction at a time until there are at
+    // least `count` of them or none can go any further. Prefixes that reach the target are
+    // already finished, so they are kept as they are.
+    fn prefixes(&self, count: usize) -> Vec<(Vec<usize>, usize)> {
+        let (target, _) = self.target();
+        let mut prefixes = vec![(vec![self.start], 0)];
+
+        while prefixes.len() < count {
+            let mut grew = false;
+            let mut next = Vec::new();
+
+            for (path, length) in prefixes {
+                let last = path[path.len() - 1];
+                if last == target {
+                    next.push((path, length));
+                    continue;
+                }
+
+                for &(to, step) in &self.edges[last] {
+                    if !path.contains(&to) && (to != self.end || to == target) {
+                        let mut path = path.clone();
+                        path.push(to);
+                        next.push((path, length + step));
+                        grew = true;
+                    }
+                }
+            }
+
+            prefixes = next;
+            if !grew {
+                break;
+            }
+        }
+
+        prefixes
+    }
+
+    fn hike(&self, (length, path): (usize, Vec<usize>)) -> Hike {
+        Hike {
+            length,
+            junctions: path.into_iter().map(|j| self.junctions[j]).collect(),
+        }
+    }
+
+    pub fn longest_hike(&self) -> Option<Hike> {
+        if let Some(order) = self.topological_order() {
+            return self.dag_longest_hike(&order);
+        }
+
+        self.search(&[self.start], 0).map(|best| self.hike(best))
+    }
+
+    // The same search split across threads, each taking some of the hikes that start out
+    // differently from the others.
+    pub fn par_longest_hike(&self) -> Option<Hike> {
+        if let Some(order) = self.topological_order() {
+            return self.dag_longest_hike(&order);
+        }
+
+        let prefixes = self.prefixes(64);
+
+        prefixes
+            .into_par_iter()
+            .enumerate()
+            .filter_map(|(i, (path, length))| self.search(&path, length).map(|best| (best, i)))
+            .max_by_key(|&((length, _), i)| (length, std::cmp::Reverse(i)))
+            .map(|(best, _)| self.hike(best))
+    }
+}
+
+struct Search<'a> {
+    graph: &'a JunctionGraph,
+    target: usize,
+    // The steps from the target to the end
+    remaining: usize,
+    path: Vec<usize>,
+    best: Option<(usize, Vec<usize>)>,
+}
+
+impl Search<'_> {
+    fn visit(&mut self, visited: u64, length: usize) {
+        let current = self.path[self.path.len() - 1];
+        if current == self.target {
+            let length = length + self.remaining;
+            if self.best.as_ref().is_none_or(|(best, _)| length > *best) {
+                let mut path = self.path.clone();
+                if self.target != self.graph.end {
+                    path.push(self.graph.end);
+                }
+                self.best = Some((length, path));
+            }
+            return;
+        }
+
+        for &(next, step) in &self.graph.edges[current] {
+            if visited & 1 << next == 0 && (next != self.graph.end || next == self.target) {
+                self.path.push(next);
+                self.visit(visited | 1 << next, length + step);
+                self.path.pop();
+            }
+        }
+    }
+}
+
+impl SlopeMode {
+    pub fn graph(self, input: &str) -> JunctionGraph {
+        let grid = Grid::from(input.lines().map(|l| l.chars().collect()).collect_vec());
+        let opening = |row: usize| {
+            let col = grid
+                .iter_row(row)
+                .position(|&cell| cell == '.')
+                .expect("Must have an opening");
+            (row, col)
+        };
+        let (start, end) = (opening(0), opening(grid.rows() - 1));
+
+        let junctions: Vec<_> = [start, end]
+            .into_iter()
+            .chain(
+                grid.indexed_iter()
+                    .filter(|&(_, &cell)| cell != '#')
+                    .map(|(position, _)| position)
+                    .filter(|&position| open_neighbors(&grid, position).count() > 2),
+            )
+            .collect();
+        assert!(junctions.len() <= 64, "Must have at most 64 junctions");
+
+        let mut graph = JunctionGraph {
+            edges: vec![Vec::new(); junctions.len()],
+            junctions,
+            start: 0,
+            end: 1,
+        };
+
+        for from in 0..graph.junctions.len() {
+            let position = graph.junctions[from];
+            for first in open_neighbors(&grid, position) {
+                let Some((to, length)) = graph.walk(&grid, self, position, first) else {
+                    continue;
+                };
+                if to == from {
+                    continue;
+                }
+
+                match graph.edges[from].iter_mut().find(|(other, _)| *other == to) {
+                    Some((_, longest)) => *longest = length.max(*longest),
+                    None => graph.edges[from].push((to, length)),
+                }
+            }
+        }
+
+        graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_junction_graph() {
+        let graph = SlopeMode::Undirected.graph(include_str!("../data/example.txt"));
+
+        assert_eq!(graph.junctions().len(), 9);
+        assert_eq!(graph.junctions()[graph.start()], (0, 1));
+        assert_eq!(graph.junctions()[graph.end()], (22, 21));
+        let [(first, 15)] = graph.edges(graph.start()) else {
+            panic!("Must lead straight to one junction");
+        };
+        assert_eq!(graph.junctions()[*first], (5, 3));
+        assert!((0..graph.junctions().len()).all(|from| graph
+            .edges(from)
+            .iter()
+            .all(|&(to, length)| graph.edges(to).contains(&(from, length)))));
+    }
+
+    #[test]
+    fn test_longest_hike() {
+        let graph = SlopeMode::Undirected.graph(include_str!("../data/example.txt"));
+        let hike = graph.longest_hike().unwrap();
+
+        assert_eq!(hike.length, 154);
+        assert_eq!(hike.junctions.first(), Some(&(0, 1)));
+        assert_eq!(hike.junctions.last(), Some(&(22, 21)));
+        assert!(hike.junctions.iter().all_unique());
+
+        let lengths: usize = hike
+            .junctions
+            .iter()
+            .tuple_windows()
+            .map(|(from, to)| {
+                let from = graph.junctions().iter().position(|j| j == from).unwrap();
+                let to = graph.junctions().iter().position(|j| j == to).unwrap();
+                graph.edges(from).iter().find(|e| e.0 == to).unwrap().1
+            })
+            .sum();
+        assert_eq!(lengths, 154);
+
+        assert_eq!(graph.par_longest_hike().unwrap().length, 154);
+    }
+
+    #[test]
+    fn test_prefixes() {
+        let graph = SlopeMode::Undirected.graph(include_str!("../data/example.txt"));
+        let (target, _) = graph.target();
+        let prefixes = graph.prefixes(64);
+
+        assert!(prefixes.len() > 1);
+        assert!(prefixes
+            .iter()
+            .any(|(path, _)| path.last() == Some(&target)));
+        assert!(prefixes.iter().all(|(path, _)| path[0] == graph.start()));
+
+        // Every finished prefix is still a candidate, even when it's all a hike can be
+        let best = prefixes
+            .iter()
+            .filter_map(|(path, length)| graph.search(path, *length))
+            .map(|(length, _)| length)
+            .max();
+        assert_eq!(best, Some(154));
+        assert_eq!(graph.par_longest_hike(), graph.longest_hike());
+    }
+
+    #[test]
+    fn test_directed() {
+        let graph = SlopeMode::Directed.graph(include_str!("../data/example.txt"));
+        let order = graph.topological_order().unwrap();
+        assert_eq!(order[0], graph.start());
+        assert!(order.iter().enumerate().all(|(i, &from)| graph
+            .edges(from)
+            .iter()
+            .all(|(to, _)| order[i..].contains(to))));
+
+        let hike = graph.longest_hike().unwrap();
+        assert_eq!(hike.length, 94);
+        assert_eq!(hike.junctions.first(), Some(&(0, 1)));
+        assert_eq!(hike.junctions.last(), Some(&(22, 21)));
+        assert_eq!(graph.par_longest_hike(), Some(hike));
+
+        let undirected = SlopeMode::Undirected.graph(include_str!("../data/example.txt"));
+        assert_eq!(undirected.topological_order(), None);
+    }
+
+    #[test]
+    fn test_directed_with_cycle() {
+        // A loop of slopes that can be walked round but never back down
+        let input = [
+            "#.#####", //
+            "#.....#", //
+            "#.###.#", //
+            "#.>.<.#", //
+            "#.###v#", //
+            "#.....#", //
+            "#####.#", //
+        ]
+        .join("\n");
+        let graph = SlopeMode::Directed.graph(&input);
+        assert_eq!(graph.topological_order(), None);
+        assert_eq!(graph.longest_hike().map(|hike| hike.length), Some(10));
+    }
+}
@@ -1,2 +1,3 @@
+pub mod junction;
 pub mod part1;
 pub mod part2;
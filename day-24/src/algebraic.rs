@@ -0,0 +1,138 @@
+use itertools::Itertools;
+use num::{BigInt, BigRational, ToPrimitive, Zero};
+
+use crate::hailstone::Hailstone;
+
+fn subtract(a: [i128; 3], b: [i128; 3]) -> [i128; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn cross(a: [i128; 3], b: [i128; 3]) -> [i128; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+/*
+The rock (P, V) and a hailstone (p, v) collide when P + t * V = p + t * v for some t. That
+means P - p and V - v point the same way, so their cross product is zero:
+
+(P - p) x (V - v) = P x V - P x v - p x V + p x v = 0
+
+The P x V term is the same for every hailstone, so taking the equation for one hailstone
+away from another's leaves something linear in the six unknowns:
+
+P x (v1 - v2) + (p1 - p2) x V = p1 x v1 - p2 x v2
+
+Each pair of hailstones gives three of these, so two pairs are enough to pin the rock down.
+*/
+fn equations(a: &Hailstone, b: &Hailstone) -> [[i128; 7]; 3] {
+    let widen = |vector: [i64; 3]| vector.map(i128::from);
+    let (pa, va) = (widen(a.position), widen(a.velocity));
+    let (pb, vb) = (widen(b.position), widen(b.velocity));
+
+    let dv = subtract(va, vb);
+    let dp = subtract(pa, pb);
+    let c = subtract(cross(pa, va), cross(pb, vb));
+
+    [
+        [0, dv[2], -dv[1], 0, -dp[2], dp[1], c[0]],
+        [-dv[2], 0, dv[0], dp[2], 0, -dp[0], c[1]],
+        [dv[1], -dv[0], 0, -dp[1], dp[0], 0, c[2]],
+    ]
+}
+
+// Gauss-Jordan elimination over exact fractions, or nothing if there isn't one solution.
+fn solve(mut rows: Vec<Vec<BigRational>>) -> Option<Vec<BigRational>> {
+    let unknowns = rows.len();
+
+    for column in 0..unknowns {
+        let pivot = (column..unknowns).find(|&row| !rows[row][column].is_zero())?;
+        rows.swap(column, pivot);
+
+        let pivot = rows[column].clone();
+        for (index, row) in rows.iter_mut().enumerate() {
+            if index == column || row[column].is_zero() {
+                continue;
+            }
+
+            let factor = &row[column] / &pivot[column];
+            for (value, pivot) in row.iter_mut().zip(&pivot) {
+                *value -= &factor * pivot;
+            }
+        }
+    }
+
+    Some(
+        rows.iter()
+            .enumerate()
+            .map(|(index, row)| &row[unknowns] / &row[index])
+            .collect(),
+    )
+}
+
+// Finds the rock from the first three hailstones whose equations have a single solution,
+// and checks it against the rest.
+pub fn solve_rock(hailstones: &[Hailstone]) -> Option<Hailstone> {
+    hailstones
+        .iter()
+        .tuple_combinations()
+        .find_map(|(a, b, c)| {
+            let rows = equations(a, b)
+                .into_iter()
+                .chain(equations(a, c))
+                .map(|row| {
+                    row.iter()
+                        .map(|&n| BigRational::from_integer(BigInt::from(n)))
+                        .collect()
+                })
+                .collect();
+
+            let solution = solve(rows)?
+                .into_iter()
+                .map(|n| n.is_integer().then(|| n.to_integer().to_i64()).flatten())
+                .collect::<Option<Vec<_>>>()?;
+
+            Some(Hailstone::from((
+                [solution[0], solution[1], solution[2]],
+                [solution[3], solution[4], solution[5]],
+            )))
+        })
+        .filter(|rock| {
+            hailstones
+                .iter()
+                .all(|hailstone| rock.collides_with(hailstone))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hailstone::parser;
+
+    #[test]
+    fn test_solve_rock() {
+        let (_, hailstones) = parser::parse(include_str!("../data/example.txt")).unwrap();
+        let rock = solve_rock(&hailstones).unwrap();
+
+        assert_eq!(rock, Hailstone::from(([24, 13, 10], [-3, 1, 2])));
+        assert_eq!(rock.position.iter().sum::<i64>(), 47);
+    }
+
+    #[test]
+    fn test_parallel_hailstones() {
+        // The first two are parallel, so the rock can't be found from them alone
+        let (_, mut hailstones) = parser::parse(include_str!("../data/example.txt")).unwrap();
+        hailstones.insert(1, Hailstone::from(([22, 13, 18], [-2, 1, -2])));
+        assert_eq!(
+            solve_rock(&hailstones),
+            Some(Hailstone::from(([24, 13, 10], [-3, 1, 2])))
+        );
+
+        // No rock hits this one as well as the rest
+        hailstones.push(Hailstone::from(([21, 12, 32], [-2, 1, -2])));
+        assert_eq!(solve_rock(&hailstones), None);
+    }
+}
@@ -0,0 +1,82 @@
+// A hailstone's position at time zero and how far it moves every nanosecond
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Hailstone {
+    pub position: [i64; 3],
+    pub velocity: [i64; 3],
+}
+
+impl Hailstone {
+    // Whether both are in exactly the same place at the same whole, non-negative time
+    pub fn collides_with(&self, other: &Self) -> bool {
+        let Some(axis) = (0..3).find(|&axis| self.velocity[axis] != other.velocity[axis]) else {
+            return self.position == other.position;
+        };
+
+        let distance = other.position[axis] - self.position[axis];
+        let closing = self.velocity[axis] - other.velocity[axis];
+        if distance % closing != 0 || distance / closing < 0 {
+            return false;
+        }
+
+        let time = distance / closing;
+        (0..3).all(|axis| {
+            self.position[axis] + self.velocity[axis] * time
+                == other.position[axis] + other.velocity[axis] * time
+        })
+    }
+}
+
+impl From<([i64; 3], [i64; 3])> for Hailstone {
+    fn from((position, velocity): ([i64; 3], [i64; 3])) -> Self {
+        Self { position, velocity }
+    }
+}
+
+pub mod parser {
+    use super::*;
+    use nom::{
+        bytes::complete::tag,
+        character::complete::{self, line_ending},
+        combinator::map,
+        multi::separated_list1,
+        sequence::{separated_pair, terminated, tuple},
+        IResult,
+    };
+
+    fn point(input: &str) -> IResult<&str, [i64; 3]> {
+        map(
+            tuple((
+                terminated(complete::i64, tag(", ")),
+                terminated(complete::i64, tag(", ")),
+                complete::i64,
+            )),
+            |(x, y, z)| [x, y, z],
+        )(input)
+    }
+
+    fn hailstone(input: &str) -> IResult<&str, Hailstone> {
+        map(separated_pair(point, tag(" @ "), point), Hailstone::from)(input)
+    }
+
+    pub fn parse(input: &str) -> IResult<&str, Vec<Hailstone>> {
+        separated_list1(line_ending, hailstone)(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collides_with() {
+        let rock = Hailstone::from(([24, 13, 10], [-3, 1, 2]));
+        let (_, hailstones) = parser::parse(include_str!("../data/example.txt")).unwrap();
+
+        assert!(hailstones
+            .iter()
+            .all(|hailstone| rock.collides_with(hailstone)));
+        assert!(!Hailstone::from(([24, 13, 11], [-3, 1, 2])).collides_with(&hailstones[0]));
+        // They line up, but only before the rock is thrown
+        assert!(!rock.collides_with(&Hailstone::from(([21, 14, 12], [-2, 1, 2]))));
+    }
+}
@@ -1,2 +1,10 @@
+#[cfg(not(any(feature = "algebraic", feature = "z3")))]
+compile_error!("Must enable the `algebraic` or `z3` feature to solve part 2");
+
+#[cfg(feature = "algebraic")]
+pub mod algebraic;
+pub mod hailstone;
 pub mod part1;
 pub mod part2;
+#[cfg(feature = "z3")]
+pub mod smt;
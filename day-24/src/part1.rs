@@ -1,31 +1,52 @@
-use glam::DVec2;
 use itertools::Itertools;
-use std::{f64::EPSILON, ops::Range};
+use std::ops::RangeInclusive;
 
-trait IntersectionChecks {
-    fn is_after(&self, other: &Hailstone) -> bool;
-    fn is_in_bounds(&self, bounds: &Range<f64>) -> bool;
+use crate::hailstone::{parser, Hailstone};
+
+fn cross(a: [i128; 2], b: [i128; 2]) -> i128 {
+    a[0] * b[1] - a[1] * b[0]
+}
+
+// Where two hailstones' paths cross and when each of them gets there. Everything is a
+// numerator over the same positive denominator, so nothing needs rounding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Intersection {
+    point: [i128; 2],
+    times: [i128; 2],
+    denominator: i128,
 }
 
-impl IntersectionChecks for DVec2 {
-    fn is_after(&self, other: &Hailstone) -> bool {
-        ((*self - other.position) * other.velocity)
-            .cmpgt(DVec2::ZERO)
-            .all()
+impl Intersection {
+    fn is_in_bounds(&self, bounds: &RangeInclusive<i128>) -> bool {
+        self.point.iter().all(|&axis| {
+            bounds.start() * self.denominator <= axis && axis <= bounds.end() * self.denominator
+        })
     }
 
-    fn is_in_bounds(&self, bounds: &Range<f64>) -> bool {
-        self.to_array().iter().all(|axis| bounds.contains(axis))
+    fn is_in_future(&self) -> bool {
+        self.times.iter().all(|&time| time > 0)
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Hailstone {
-    position: DVec2,
-    velocity: DVec2,
+// Only the x and y axes matter here, widened so the cross products can't overflow
+fn flatten(vector: [i64; 3]) -> [i128; 2] {
+    [vector[0].into(), vector[1].into()]
+}
+
+trait Intersections {
+    fn find_intersection(&self, other: &Self) -> Option<Intersection>;
+
+    fn find_future_intersection_in_bounds(
+        &self,
+        other: &Self,
+        bounds: &RangeInclusive<i128>,
+    ) -> Option<Intersection> {
+        self.find_intersection(other)
+            .filter(|intersection| intersection.is_in_bounds(bounds) && intersection.is_in_future())
+    }
 }
 
-impl Hailstone {
+impl Intersections for Hailstone {
     /*
     Let's talk math for a bit.
     Here's the general formula for an intersection based on initial position and constant velocities:
@@ -94,83 +115,40 @@ impl Hailstone {
     And there we have it! We've got the cross product in the denominator.
     This means we don't need to duplicate logic anymore.
 
-    While working on part2, I found it simpler to use glam for the calculations.
-    With this in mind, I simplified part1 as well. The math is all the same though.
+    Positions are around 4 * 10^14, which is more than f64 can hold exactly once multiplied
+    by a velocity, so everything stays in i128 instead. Rather than dividing by the cross
+    product, the intersection and both times are kept as numerators over it, and the checks
+    multiply through by it instead.
     */
-    fn find_intersection(&self, other: &Self) -> Option<DVec2> {
-        let cross_product = self.velocity.perp_dot(other.velocity);
-
-        if cross_product.abs() < EPSILON {
-            None
-        } else {
-            let t = (other.position - self.position).perp_dot(other.velocity) / cross_product;
-            Some(self.position + self.velocity * t)
-        }
-    }
+    fn find_intersection(&self, other: &Self) -> Option<Intersection> {
+        let (position, velocity) = (flatten(self.position), flatten(self.velocity));
+        let (other_position, other_velocity) = (flatten(other.position), flatten(other.velocity));
 
-    fn find_future_intersection_in_bounds(
-        &self,
-        other: &Self,
-        bounds: &Range<f64>,
-    ) -> Option<DVec2> {
-        let Some(intersection) = self.find_intersection(other) else {
+        let cross_product = cross(velocity, other_velocity);
+        if cross_product == 0 {
             return None;
-        };
-
-        if intersection.is_in_bounds(bounds)
-            && intersection.is_after(self)
-            && intersection.is_after(other)
-        {
-            Some(intersection)
-        } else {
-            None
         }
-    }
-}
-
-impl From<(DVec2, DVec2)> for Hailstone {
-    fn from((position, velocity): (DVec2, DVec2)) -> Self {
-        Self { position, velocity }
-    }
-}
 
-mod parser {
-    use super::*;
-    use nom::{
-        bytes::complete::tag,
-        character::complete::{self, line_ending},
-        combinator::map,
-        multi::separated_list1,
-        sequence::{separated_pair, terminated, tuple},
-        IResult,
-    };
-
-    fn number(input: &str) -> IResult<&str, f64> {
-        map(complete::i64, |number| number as f64)(input)
-    }
-
-    fn point(input: &str) -> IResult<&str, DVec2> {
-        map(
-            tuple((
-                terminated(number, tag(", ")),
-                terminated(number, tuple((tag(", "), number))),
-            )),
-            DVec2::from,
-        )(input)
-    }
-
-    fn hailstone(input: &str) -> IResult<&str, Hailstone> {
-        map(separated_pair(point, tag(" @ "), point), Hailstone::from)(input)
-    }
-
-    pub fn parse(input: &str) -> IResult<&str, Vec<Hailstone>> {
-        separated_list1(line_ending, hailstone)(input)
+        let difference = [
+            other_position[0] - position[0],
+            other_position[1] - position[1],
+        ];
+        let sign = cross_product.signum();
+        let time = cross(difference, other_velocity) * sign;
+        let other_time = cross(difference, velocity) * sign;
+        let denominator = cross_product.abs();
+
+        Some(Intersection {
+            point: [0, 1].map(|axis| position[axis] * denominator + velocity[axis] * time),
+            times: [time, other_time],
+            denominator,
+        })
     }
 }
 
 pub fn process(input: &str, low_bound: isize, high_bound: isize) -> String {
     let (_, hailstones) = parser::parse(input).expect("should parse");
-    let bounds = (low_bound as f64)..(high_bound as f64 + 1.0);
+    let bounds = (low_bound as i128)..=(high_bound as i128);
 
     hailstones
         .iter()
@@ -191,4 +169,60 @@ mod tests {
         let result = process(include_str!("../data/example.txt"), 7, 27);
         assert_eq!(result, "2".to_string());
     }
+
+    // Two hailstones that meet at `point`, `times` nanoseconds after they start
+    fn meeting(point: [i64; 2], velocities: [[i64; 2]; 2], times: [i64; 2]) -> [Hailstone; 2] {
+        [0, 1].map(|i| {
+            Hailstone::from((
+                [
+                    point[0] - velocities[i][0] * times[i],
+                    point[1] - velocities[i][1] * times[i],
+                    0,
+                ],
+                [velocities[i][0], velocities[i][1], 0],
+            ))
+        })
+    }
+
+    #[test]
+    fn test_near_parallel() {
+        let point = [300000000000001, 399999999999999];
+        let [a, b] = meeting(point, [[1000, 999], [999, 998]], [5, 7]);
+
+        let intersection = a.find_intersection(&b).unwrap();
+        assert_eq!(
+            intersection.point,
+            point.map(|axis| i128::from(axis) * intersection.denominator)
+        );
+
+        let bounds = 200000000000000..=400000000000000;
+        assert!(a.find_future_intersection_in_bounds(&b, &bounds).is_some());
+        assert!(b.find_future_intersection_in_bounds(&a, &bounds).is_some());
+
+        let [a, b] = meeting(point, [[1000, 999], [999, 998]], [5, -7]);
+        assert!(a.find_future_intersection_in_bounds(&b, &bounds).is_none());
+
+        let [a, b] = meeting(point, [[1000, 999], [2000, 1998]], [5, 7]);
+        assert_eq!(a.find_intersection(&b), None);
+    }
+
+    #[test]
+    fn test_boundary() {
+        let bounds = 7..=27;
+        let [a, b] = meeting([27, 7], [[1, 2], [-3, 1]], [1, 2]);
+        assert!(a.find_future_intersection_in_bounds(&b, &bounds).is_some());
+
+        // Crossing half a unit past the edge
+        let a = Hailstone::from(([26, 7, 0], [1, 0, 0]));
+        let b = Hailstone::from(([29, 6, 0], [-1, 2, 0]));
+        let intersection = a.find_intersection(&b).unwrap();
+        assert_eq!(intersection.point, [57, 14]);
+        assert_eq!(intersection.denominator, 2);
+        assert!(a
+            .find_future_intersection_in_bounds(&b, &(7..=28))
+            .is_none());
+        assert!(a
+            .find_future_intersection_in_bounds(&b, &(7..=29))
+            .is_some());
+    }
 }
@@ -0,0 +1,66 @@
+use z3::{
+    ast::{Ast, Int},
+    Config, Context, SatResult, Solver,
+};
+
+use crate::hailstone::Hailstone;
+
+// Hands every collision to z3 as an equation over integers and reads the rock back out.
+pub fn solve_rock(hailstones: &[Hailstone]) -> Option<Hailstone> {
+    let cfg = Config::new();
+    let ctx = Context::new(&cfg);
+    let solver = Solver::new(&ctx);
+
+    let initial_rock = ["x", "y", "z"].map(|name| Int::new_const(&ctx, name));
+    let velocity_rock = ["vx", "vy", "vz"].map(|name| Int::new_const(&ctx, name));
+
+    for (index, hailstone) in hailstones.iter().enumerate() {
+        let t = Int::new_const(&ctx, format!("t{index}"));
+        solver.assert(&t.ge(&Int::from_i64(&ctx, 0)));
+
+        for axis in 0..3 {
+            let hailstone_axis = hailstone.position[axis] + &t * hailstone.velocity[axis];
+            let rock_axis = &initial_rock[axis] + &t * &velocity_rock[axis];
+            solver.assert(&hailstone_axis._eq(&rock_axis));
+        }
+    }
+
+    let SatResult::Sat = solver.check() else {
+        return None;
+    };
+
+    let model = solver.get_model()?;
+    let value = |constant: &Int| {
+        model
+            .get_const_interp(constant)
+            .and_then(|ast| ast.as_i64())
+    };
+
+    Some(Hailstone::from((
+        [
+            value(&initial_rock[0])?,
+            value(&initial_rock[1])?,
+            value(&initial_rock[2])?,
+        ],
+        [
+            value(&velocity_rock[0])?,
+            value(&velocity_rock[1])?,
+            value(&velocity_rock[2])?,
+        ],
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hailstone::parser;
+
+    #[test]
+    fn test_solve_rock() {
+        let (_, hailstones) = parser::parse(include_str!("../data/example.txt")).unwrap();
+        let rock = solve_rock(&hailstones).unwrap();
+
+        assert_eq!(rock, Hailstone::from(([24, 13, 10], [-3, 1, 2])));
+        assert_eq!(rock.position.iter().sum::<i64>(), 47);
+    }
+}
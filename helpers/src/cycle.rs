@@ -0,0 +1,401 @@
+use std::{collections::HashMap, hash::Hash};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    // The number of steps taken before the first state that repeats
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    // The smallest number of steps that ends in the same state as `steps` steps.
+    pub fn reduce(&self, steps: usize) -> usize {
+        if steps < self.start {
+            steps
+        } else {
+            self.start + (steps - self.start) % self.length
+        }
+    }
+}
+
+pub fn floyd<T: Clone + Eq>(initial: &T, step: impl FnMut(&T) -> T) -> Cycle {
+    floyd_by_key(initial, step, T::clone)
+}
+
+// Floyd's tortoise and hare, comparing states by `key` (a hash, for example).
+pub fn floyd_by_key<T: Clone, K: PartialEq>(
+    initial: &T,
+    mut step: impl FnMut(&T) -> T,
+    key: impl Fn(&T) -> K,
+) -> Cycle {
+    let mut tortoise = step(initial);
+    let mut hare = step(&tortoise);
+    while key(&tortoise) != key(&hare) {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    let mut start = 0;
+    tortoise = initial.clone();
+    while key(&tortoise) != key(&hare) {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+    while key(&tortoise) != key(&hare) {
+        hare = step(&hare);
+        length += 1;
+    }
+
+    Cycle { start, length }
+}
+
+pub fn brent<T: Clone + Eq>(initial: &T, step: impl FnMut(&T) -> T) -> Cycle {
+    brent_by_key(initial, step, T::clone)
+}
+
+// Brent's algorithm, comparing states by `key` (a hash, for example).
+pub fn brent_by_key<T: Clone, K: PartialEq>(
+    initial: &T,
+    mut step: impl FnMut(&T) -> T,
+    key: impl Fn(&T) -> K,
+) -> Cycle {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(initial);
+    while key(&tortoise) != key(&hare) {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+
+        hare = step(&hare);
+        length += 1;
+    }
+
+    let mut start = 0;
+    tortoise = initial.clone();
+    hare = (0..length).fold(initial.clone(), |state, _| step(&state));
+    while key(&tortoise) != key(&hare) {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, length }
+}
+
+// Steps forward `steps` times, skipping whole loops once a state is seen twice.
+pub fn fast_forward<T: Clone + Hash + Eq>(
+    state: T,
+    mut step: impl FnMut(&T) -> T,
+    steps: usize,
+) -> T {
+    let mut seen = HashMap::from([(state.clone(), 0)]);
+    let mut history = vec![state];
+
+    for index in 1..=steps {
+        let next = step(&history[index - 1]);
+
+        if let Some(&start) = seen.get(&next) {
+            let cycle = Cycle {
+                start,
+                length: index - start,
+            };
+            return history.swap_remove(cycle.reduce(steps));
+        }
+
+        seen.insert(next.clone(), index);
+        history.push(next);
+    }
+
+    history.swap_remove(steps)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Period {
+    // The step a signal first fires on
+    pub first: usize,
+    pub length: usize,
+}
+
+impl Period {
+    pub fn fires_at(&self, step: usize) -> bool {
+        step >= self.first && (step - self.first).is_multiple_of(self.length)
+    }
+}
+
+// Watches a stream of `(signal, step)` events and reports each signal's period once it
+// has fired `confirmations` more times at the same interval.
+#[derive(Debug, Clone)]
+pub struct SignalPeriods<K> {
+    confirmations: usize,
+    events: HashMap<K, Vec<usize>>,
+}
+
+impl<K: Hash + Eq> Default for SignalPeriods<K> {
+    fn default() -> Self {
+        Self::new(2)
+    }
+}
+
+impl<K: Hash + Eq> SignalPeriods<K> {
+    pub fn new(confirmations: usize) -> Self {
+        Self {
+            confirmations: confirmations.max(1),
+            events: HashMap::new(),
+        }
+    }
+
+    pub fn record(&mut self, signal: K, step: usize) {
+        let steps = self.events.entry(signal).or_default();
+        if steps.last() != Some(&step) {
+            steps.push(step);
+        }
+    }
+
+    pub fn period(&self, signal: &K) -> Option<Period> {
+        let steps = self.events.get(signal)?;
+        let first = *steps.first()?;
+        let length = steps.get(1)? - first;
+
+        (steps.len() > self.confirmations
+            && steps
+                .iter()
+                .zip(steps.iter().skip(1))
+                .all(|(a, b)| b - a == length))
+        .then_some(Period { first, length })
+    }
+
+    pub fn periods<'a>(&'a self, signals: impl IntoIterator<Item = &'a K>) -> Option<Vec<Period>> {
+        signals
+            .into_iter()
+            .map(|signal| self.period(signal))
+            .collect()
+    }
+}
+
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (gcd, x, y) = extended_gcd(b, a % b);
+        (gcd, y, x - (a / b) * y)
+    }
+}
+
+// The first step on which every period fires together, if they ever do. A period of length
+// zero only ever fires on its first step.
+pub fn first_common_step(periods: impl IntoIterator<Item = Period>) -> Option<usize> {
+    let periods: Vec<_> = periods.into_iter().collect();
+    if let Some(once) = periods.iter().find(|period| period.length == 0) {
+        return periods
+            .iter()
+            .all(|period| period.fires_at(once.first))
+            .then_some(once.first);
+    }
+
+    let (residue, modulus, earliest) = periods.into_iter().try_fold(
+        (0_i128, 1_i128, 0_usize),
+        |(residue, modulus, earliest), period| {
+            let length = period.length as i128;
+            let target = period.first as i128 % length;
+            let (gcd, x, _) = extended_gcd(modulus, length);
+            if (target - residue) % gcd != 0 {
+                return None;
+            }
+
+            let lcm = modulus / gcd * length;
+            let offset = ((target - residue) / gcd * x).rem_euclid(length / gcd);
+            Some((
+                (residue + modulus * offset).rem_euclid(lcm),
+                lcm,
+                earliest.max(period.first),
+            ))
+        },
+    )?;
+
+    let earliest = earliest as i128;
+    let step = if residue >= earliest {
+        residue
+    } else {
+        residue + (earliest - residue + modulus - 1) / modulus * modulus
+    };
+
+    usize::try_from(step).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(n: &usize) -> usize {
+        (n * n + 1) % 255
+    }
+
+    #[test]
+    fn floyd_test() {
+        assert_eq!(
+            floyd(&0, step),
+            Cycle {
+                start: 2,
+                length: 6
+            }
+        );
+        assert_eq!(
+            floyd(&3, |n| (n + 1) % 5),
+            Cycle {
+                start: 0,
+                length: 5
+            }
+        );
+    }
+
+    #[test]
+    fn brent_test() {
+        assert_eq!(
+            brent(&0, step),
+            Cycle {
+                start: 2,
+                length: 6
+            }
+        );
+        assert_eq!(
+            brent_by_key(&(0, "ignored"), |(n, tag)| (step(n), *tag), |(n, _)| *n),
+            Cycle {
+                start: 2,
+                length: 6
+            }
+        );
+    }
+
+    #[test]
+    fn fast_forward_test() {
+        let naive = |steps: usize| (0..steps).fold(0, |n, _| step(&n));
+
+        for steps in [0, 1, 2, 7, 8, 100, 1_000] {
+            assert_eq!(fast_forward(0, step, steps), naive(steps));
+        }
+        assert_eq!(
+            fast_forward(0, step, 1_000_000_000),
+            naive(2 + (1_000_000_000 - 2) % 6)
+        );
+    }
+
+    #[test]
+    fn signal_periods_test() {
+        let mut periods = SignalPeriods::default();
+        for step in 1..=100 {
+            if step % 7 == 0 {
+                periods.record("a", step);
+            }
+            if step % 11 == 3 {
+                periods.record("b", step);
+            }
+        }
+        periods.record("c", 5);
+        periods.record("c", 10);
+
+        assert_eq!(
+            periods.period(&"a"),
+            Some(Period {
+                first: 7,
+                length: 7
+            })
+        );
+        assert_eq!(
+            periods.period(&"b"),
+            Some(Period {
+                first: 3,
+                length: 11
+            })
+        );
+        assert_eq!(periods.period(&"c"), None);
+        assert_eq!(periods.periods(&["a", "c"]), None);
+    }
+
+    #[test]
+    fn first_common_step_test() {
+        let periods = [
+            Period {
+                first: 7,
+                length: 7,
+            },
+            Period {
+                first: 3,
+                length: 11,
+            },
+        ];
+        let step = first_common_step(periods).unwrap();
+
+        assert_eq!(step, 14);
+        assert!(periods.iter().all(|period| period.fires_at(step)));
+        assert_eq!(
+            first_common_step([
+                Period {
+                    first: 4,
+                    length: 4
+                },
+                Period {
+                    first: 6,
+                    length: 6
+                }
+            ]),
+            Some(12)
+        );
+        assert_eq!(
+            first_common_step([
+                Period {
+                    first: 0,
+                    length: 4
+                },
+                Period {
+                    first: 1,
+                    length: 6
+                }
+            ]),
+            None
+        );
+    }
+
+    #[test]
+    fn first_common_step_zero_length_test() {
+        let once = Period {
+            first: 14,
+            length: 0,
+        };
+        let sevens = Period {
+            first: 7,
+            length: 7,
+        };
+
+        assert_eq!(first_common_step([once]), Some(14));
+        assert_eq!(first_common_step([sevens, once]), Some(14));
+        assert_eq!(
+            first_common_step([
+                sevens,
+                Period {
+                    first: 15,
+                    length: 0
+                }
+            ]),
+            None
+        );
+        assert_eq!(
+            first_common_step([
+                once,
+                Period {
+                    first: 3,
+                    length: 0
+                }
+            ]),
+            None
+        );
+    }
+}
@@ -1,3 +1,6 @@
+pub mod cycle;
+pub mod search;
+
 use std::marker::PhantomData;
 
 pub fn indexed_iter<T>(
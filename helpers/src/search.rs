@@ -0,0 +1,591 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, VecDeque},
+};
+
+// A graph whose states can be numbered densely, so searches can keep their bookkeeping
+// in flat arrays instead of hash maps.
+pub trait SearchSpace {
+    type State: Copy;
+
+    fn state_count(&self) -> usize;
+    fn index(&self, state: Self::State) -> usize;
+    fn state(&self, index: usize) -> Self::State;
+
+    // Pushes every `(next state, weight)` reachable from `state`.
+    fn successors(&self, state: Self::State, successors: &mut Vec<(Self::State, usize)>);
+
+    // Pushes every `(previous state, weight)` that reaches `state`. Only bidirectional
+    // search needs this, and the default assumes every edge can be walked both ways.
+    fn predecessors(&self, state: Self::State, predecessors: &mut Vec<(Self::State, usize)>) {
+        self.successors(state, predecessors);
+    }
+
+    // When every weight is known to be at most this, a bucket queue replaces the heap.
+    fn max_weight(&self) -> Option<usize> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Path<S> {
+    pub cost: usize,
+    pub states: Vec<S>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SearchResult<S> {
+    pub path: Option<Path<S>>,
+    // The number of states taken off the frontier and expanded
+    pub expanded: usize,
+}
+
+const NONE: usize = usize::MAX;
+
+// Dial's algorithm: one bucket per priority, which only ever moves forwards. Emptied
+// buckets are moved to the back, so the buckets form a ring that only grows when a
+// priority lands further ahead of the last pop than it has before.
+#[derive(Debug, Clone, Default)]
+pub struct BucketQueue {
+    base: usize,
+    buckets: VecDeque<Vec<usize>>,
+    len: usize,
+}
+
+impl BucketQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // A ring big enough for every push to land at most `span` after the last pop.
+    pub fn with_span(span: usize) -> Self {
+        Self {
+            base: 0,
+            buckets: (0..=span).map(|_| Vec::new()).collect(),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push(&mut self, priority: usize, item: usize) {
+        assert!(
+            priority >= self.base,
+            "A bucket queue can only push priorities at or after the last pop"
+        );
+
+        let offset = priority - self.base;
+        if offset >= self.buckets.len() {
+            self.buckets.resize_with(offset + 1, Vec::new);
+        }
+
+        self.buckets[offset].push(item);
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<(usize, usize)> {
+        if self.is_empty() {
+            return None;
+        }
+
+        loop {
+            if let Some(item) = self.buckets.front_mut().and_then(|bucket| bucket.pop()) {
+                self.len -= 1;
+                return Some((self.base, item));
+            }
+
+            self.buckets.rotate_left(1);
+            self.base += 1;
+        }
+    }
+}
+
+enum Frontier {
+    Heap(BinaryHeap<Reverse<(usize, usize)>>),
+    Buckets(BucketQueue),
+}
+
+impl Frontier {
+    fn for_space<G: SearchSpace>(space: &G) -> Self {
+        match space.max_weight() {
+            Some(max_weight) => Self::Buckets(BucketQueue::with_span(max_weight)),
+            None => Self::Heap(BinaryHeap::new()),
+        }
+    }
+
+    fn push(&mut self, priority: usize, item: usize) {
+        match self {
+            Self::Heap(heap) => heap.push(Reverse((priority, item))),
+            Self::Buckets(buckets) => buckets.push(priority, item),
+        }
+    }
+
+    fn pop(&mut self) -> Option<(usize, usize)> {
+        match self {
+            Self::Heap(heap) => heap.pop().map(|Reverse(entry)| entry),
+            Self::Buckets(buckets) => buckets.pop(),
+        }
+    }
+
+    fn peek_priority(&mut self) -> Option<usize> {
+        match self {
+            Self::Heap(heap) => heap.peek().map(|Reverse((priority, _))| *priority),
+            Self::Buckets(buckets) => buckets.pop().map(|(priority, item)| {
+                buckets.push(priority, item);
+                priority
+            }),
+        }
+    }
+}
+
+fn reconstruct<G: SearchSpace>(space: &G, parents: &[usize], mut index: usize) -> Vec<G::State> {
+    let mut states = vec![space.state(index)];
+    while parents[index] != NONE {
+        index = parents[index];
+        states.push(space.state(index));
+    }
+
+    states.reverse();
+    states
+}
+
+// Every edge counts as one step, whatever its weight.
+pub fn bfs<G: SearchSpace>(
+    space: &G,
+    starts: impl IntoIterator<Item = G::State>,
+    is_goal: impl Fn(G::State) -> bool,
+) -> SearchResult<G::State> {
+    let mut distances = vec![NONE; space.state_count()];
+    let mut parents = vec![NONE; space.state_count()];
+    let mut queue = VecDeque::new();
+    let mut successors = Vec::new();
+    let mut expanded = 0;
+
+    for start in starts {
+        let index = space.index(start);
+        if distances[index] == NONE {
+            distances[index] = 0;
+            queue.push_back(index);
+        }
+    }
+
+    while let Some(index) = queue.pop_front() {
+        expanded += 1;
+        let state = space.state(index);
+
+        if is_goal(state) {
+            return SearchResult {
+                path: Some(Path {
+                    cost: distances[index],
+                    states: reconstruct(space, &parents, index),
+                }),
+                expanded,
+            };
+        }
+
+        successors.clear();
+        space.successors(state, &mut successors);
+        for &(next, _) in &successors {
+            let next_index = space.index(next);
+            if distances[next_index] == NONE {
+                distances[next_index] = distances[index] + 1;
+                parents[next_index] = index;
+                queue.push_back(next_index);
+            }
+        }
+    }
+
+    SearchResult {
+        path: None,
+        expanded,
+    }
+}
+
+pub fn dijkstra<G: SearchSpace>(
+    space: &G,
+    starts: impl IntoIterator<Item = G::State>,
+    is_goal: impl Fn(G::State) -> bool,
+) -> SearchResult<G::State> {
+    astar(space, starts, is_goal, |_| 0)
+}
+
+// The heuristic must never overestimate, and must be consistent when a bucket queue is used.
+pub fn astar<G: SearchSpace>(
+    space: &G,
+    starts: impl IntoIterator<Item = G::State>,
+    is_goal: impl Fn(G::State) -> bool,
+    heuristic: impl Fn(G::State) -> usize,
+) -> SearchResult<G::State> {
+    let mut costs = vec![NONE; space.state_count()];
+    let mut parents = vec![NONE; space.state_count()];
+    let mut frontier = Frontier::for_space(space);
+    let mut successors = Vec::new();
+    let mut expanded = 0;
+
+    for start in starts {
+        let index = space.index(start);
+        costs[index] = 0;
+        frontier.push(heuristic(start), index);
+    }
+
+    while let Some((priority, index)) = frontier.pop() {
+        let state = space.state(index);
+        if priority > costs[index] + heuristic(state) {
+            continue;
+        }
+
+        expanded += 1;
+
+        if is_goal(state) {
+            return SearchResult {
+                path: Some(Path {
+                    cost: costs[index],
+                    states: reconstruct(space, &parents, index),
+                }),
+                expanded,
+            };
+        }
+
+        successors.clear();
+        space.successors(state, &mut successors);
+        for &(next, weight) in &successors {
+            let next_index = space.index(next);
+            let cost = costs[index] + weight;
+
+            if cost < costs[next_index] {
+                costs[next_index] = cost;
+                parents[next_index] = index;
+                frontier.push(cost + heuristic(next), next_index);
+            }
+        }
+    }
+
+    SearchResult {
+        path: None,
+        expanded,
+    }
+}
+
+struct Direction {
+    costs: Vec<usize>,
+    parents: Vec<usize>,
+    frontier: Frontier,
+}
+
+impl Direction {
+    fn new<G: SearchSpace>(space: &G, start: G::State) -> Self {
+        let mut direction = Self {
+            costs: vec![NONE; space.state_count()],
+            parents: vec![NONE; space.state_count()],
+            frontier: Frontier::for_space(space),
+        };
+
+        let index = space.index(start);
+        direction.costs[index] = 0;
+        direction.frontier.push(0, index);
+        direction
+    }
+}
+
+// Dijkstra from both ends at once, stopping once the two frontiers can no longer
+// improve on the best meeting point found so far.
+pub fn bidirectional<G: SearchSpace>(
+    space: &G,
+    start: G::State,
+    goal: G::State,
+) -> SearchResult<G::State> {
+    let mut forward = Direction::new(space, start);
+    let mut backward = Direction::new(space, goal);
+    let mut neighbors = Vec::new();
+    let mut best: Option<(usize, usize)> = None;
+    let mut expanded = 0;
+
+    while let (Some(forward_top), Some(backward_top)) = (
+        forward.frontier.peek_priority(),
+        backward.frontier.peek_priority(),
+    ) {
+        if best.is_some_and(|(cost, _)| forward_top + backward_top >= cost) {
+            break;
+        }
+
+        let is_forward = forward_top <= backward_top;
+        let (this, other) = if is_forward {
+            (&mut forward, &backward)
+        } else {
+            (&mut backward, &forward)
+        };
+
+        let Some((cost, index)) = this.frontier.pop() else {
+            break;
+        };
+        if cost > this.costs[index] {
+            continue;
+        }
+
+        expanded += 1;
+        neighbors.clear();
+        if is_forward {
+            space.successors(space.state(index), &mut neighbors);
+        } else {
+            space.predecessors(space.state(index), &mut neighbors);
+        }
+
+        for &(next, weight) in &neighbors {
+            let next_index = space.index(next);
+            let next_cost = cost + weight;
+
+            if next_cost < this.costs[next_index] {
+                this.costs[next_index] = next_cost;
+                this.parents[next_index] = index;
+                this.frontier.push(next_cost, next_index);
+            }
+
+            if other.costs[next_index] != NONE {
+                let total = this.costs[next_index] + other.costs[next_index];
+                if best.is_none_or(|(cost, _)| total < cost) {
+                    best = Some((total, next_index));
+                }
+            }
+        }
+    }
+
+    if space.index(start) == space.index(goal) {
+        best = Some((0, space.index(start)));
+    }
+
+    let path = best.map(|(cost, meeting)| {
+        let mut states = reconstruct(space, &forward.parents, meeting);
+        let mut index = meeting;
+        while backward.parents[index] != NONE {
+            index = backward.parents[index];
+            states.push(space.state(index));
+        }
+
+        Path { cost, states }
+    });
+
+    SearchResult { path, expanded }
+}
+
+// Four-way movement over a flat, row-major grid, where `cost` gives the weight of
+// stepping onto a cell, or `None` for a wall.
+pub struct GridGraph<'a, T, F> {
+    grid: &'a [T],
+    size: (usize, usize),
+    cost: F,
+    max_weight: Option<usize>,
+}
+
+impl<'a, T, F: Fn(&T) -> Option<usize>> GridGraph<'a, T, F> {
+    pub fn new(grid: &'a [T], size: (usize, usize), cost: F) -> Self {
+        Self {
+            grid,
+            size,
+            cost,
+            max_weight: None,
+        }
+    }
+
+    pub fn with_max_weight(mut self, max_weight: usize) -> Self {
+        self.max_weight = Some(max_weight);
+        self
+    }
+
+    fn cell_cost(&self, (row, col): (usize, usize)) -> Option<usize> {
+        (self.cost)(&self.grid[row * self.size.1 + col])
+    }
+
+    fn neighbors(&self, (row, col): (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+        let (rows, cols) = self.size;
+
+        [
+            row.checked_sub(1).map(|row| (row, col)),
+            (row + 1 < rows).then_some((row + 1, col)),
+            col.checked_sub(1).map(|col| (row, col)),
+            (col + 1 < cols).then_some((row, col + 1)),
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
+impl<T, F: Fn(&T) -> Option<usize>> SearchSpace for GridGraph<'_, T, F> {
+    type State = (usize, usize);
+
+    fn state_count(&self) -> usize {
+        self.size.0 * self.size.1
+    }
+
+    fn index(&self, (row, col): Self::State) -> usize {
+        row * self.size.1 + col
+    }
+
+    fn state(&self, index: usize) -> Self::State {
+        (index / self.size.1, index % self.size.1)
+    }
+
+    fn successors(&self, state: Self::State, successors: &mut Vec<(Self::State, usize)>) {
+        successors.extend(
+            self.neighbors(state)
+                .filter_map(|next| self.cell_cost(next).map(|cost| (next, cost))),
+        );
+    }
+
+    fn predecessors(&self, state: Self::State, predecessors: &mut Vec<(Self::State, usize)>) {
+        if let Some(cost) = self.cell_cost(state) {
+            predecessors.extend(
+                self.neighbors(state)
+                    .filter(|&previous| self.cell_cost(previous).is_some())
+                    .map(|previous| (previous, cost)),
+            );
+        }
+    }
+
+    fn max_weight(&self) -> Option<usize> {
+        self.max_weight
+    }
+}
+
+pub fn manhattan((row, col): (usize, usize)) -> impl Fn((usize, usize)) -> usize {
+    move |(other_row, other_col)| row.abs_diff(other_row) + col.abs_diff(other_col)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAZE: &str = "\
+        S.#.....\
+        .##.###.\
+        ....#...\
+        .##...#E";
+
+    fn maze() -> Vec<char> {
+        MAZE.chars().collect()
+    }
+
+    fn weights() -> Vec<usize> {
+        "1163751742138137369412853168"
+            .bytes()
+            .map(|b| (b - b'0') as usize)
+            .collect()
+    }
+
+    fn is_valid<T, F: Fn(&T) -> Option<usize>>(
+        graph: &GridGraph<T, F>,
+        path: &Path<(usize, usize)>,
+    ) {
+        let cost = path
+            .states
+            .windows(2)
+            .map(|pair| {
+                assert_eq!(manhattan(pair[0])(pair[1]), 1);
+                graph
+                    .cell_cost(pair[1])
+                    .expect("should not walk through walls")
+            })
+            .sum::<usize>();
+        assert_eq!(cost, path.cost);
+    }
+
+    #[test]
+    fn bucket_queue_test() {
+        let mut queue = BucketQueue::new();
+        queue.push(3, 30);
+        queue.push(1, 10);
+        queue.push(1, 11);
+        assert_eq!(queue.pop().map(|(priority, _)| priority), Some(1));
+        queue.push(2, 20);
+        assert_eq!(queue.len(), 3);
+
+        let popped = std::iter::from_fn(|| queue.pop()).collect::<Vec<_>>();
+        assert_eq!(popped.len(), 3);
+        assert_eq!(popped[1..], [(2, 20), (3, 30)]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn bucket_ring_test() {
+        let mut queue = BucketQueue::with_span(3);
+        assert_eq!(queue.buckets.len(), 4);
+
+        for priority in 0..3 {
+            queue.push(priority, priority);
+        }
+        for step in 0..20 {
+            assert_eq!(queue.pop(), Some((step, step)));
+            queue.push(step + 3, step + 3);
+        }
+        assert_eq!(queue.buckets.len(), 4);
+
+        queue.push(30, 30);
+        assert_eq!(queue.buckets.len(), 12);
+        let popped = std::iter::from_fn(|| queue.pop()).collect::<Vec<_>>();
+        assert_eq!(popped, [(20, 20), (21, 21), (22, 22), (30, 30)]);
+    }
+
+    #[test]
+    fn bfs_test() {
+        let grid = maze();
+        let graph = GridGraph::new(&grid, (4, 8), |&c| (c != '#').then_some(1));
+        let result = bfs(&graph, [(0, 0)], |state| state == (3, 7));
+        let path = result.path.unwrap();
+
+        assert_eq!(path.cost, 12);
+        assert_eq!(path.states.first(), Some(&(0, 0)));
+        assert_eq!(path.states.last(), Some(&(3, 7)));
+        is_valid(&graph, &path);
+        assert!(bfs(&graph, [(0, 0)], |state| state == (0, 2))
+            .path
+            .is_none());
+    }
+
+    #[test]
+    fn weighted_search_test() {
+        let grid = weights();
+        let heap = GridGraph::new(&grid, (4, 7), |&w| Some(w));
+        let buckets = GridGraph::new(&grid, (4, 7), |&w| Some(w)).with_max_weight(9);
+        let goal = (3, 6);
+
+        let expected = dijkstra(&heap, [(0, 0)], |state| state == goal);
+        let cost = expected.path.as_ref().unwrap().cost;
+        is_valid(&heap, expected.path.as_ref().unwrap());
+
+        for result in [
+            dijkstra(&buckets, [(0, 0)], |state| state == goal),
+            astar(&heap, [(0, 0)], |state| state == goal, manhattan(goal)),
+            astar(&buckets, [(0, 0)], |state| state == goal, manhattan(goal)),
+            bidirectional(&heap, (0, 0), goal),
+            bidirectional(&buckets, (0, 0), goal),
+        ] {
+            let path = result.path.unwrap();
+            assert_eq!(path.cost, cost);
+            assert_eq!(path.states.first(), Some(&(0, 0)));
+            assert_eq!(path.states.last(), Some(&goal));
+            is_valid(&heap, &path);
+            assert!(result.expanded > 0);
+        }
+
+        let astar_expanded =
+            astar(&heap, [(0, 0)], |state| state == goal, manhattan(goal)).expanded;
+        assert!(astar_expanded <= expected.expanded);
+    }
+
+    #[test]
+    fn bidirectional_test() {
+        let grid = maze();
+        let graph = GridGraph::new(&grid, (4, 8), |&c| (c != '#').then_some(1));
+        let path = bidirectional(&graph, (0, 0), (3, 7)).path.unwrap();
+
+        assert_eq!(path.cost, 12);
+        is_valid(&graph, &path);
+        assert_eq!(bidirectional(&graph, (2, 2), (2, 2)).path.unwrap().cost, 0);
+        assert!(bidirectional(&graph, (0, 0), (0, 2)).path.is_none());
+    }
+}